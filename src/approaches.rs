@@ -0,0 +1,204 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    constants::*,
+    events::{Checkpoint, ClockJumped, EventLog, EventObserver, LoggedEvent},
+    format_distance, Coord, Kind, Primary, SimClock, Trajectory, Velocity,
+};
+
+type Body<'a> = (
+    Entity,
+    &'a Name,
+    &'a Coord,
+    &'a Velocity,
+    &'a Kind,
+    Option<&'a Primary>,
+);
+
+/// Last three samples of a monitored quantity for one pair of bodies.
+#[derive(Clone, Copy, Default)]
+struct Samples {
+    values: [f64; 3],
+    /// Samples taken so far, up to three
+    count: usize,
+}
+
+impl Samples {
+    fn push(&mut self, value: f64) {
+        self.values = [self.values[1], self.values[2], value];
+        self.count = (self.count + 1).min(3);
+    }
+
+    /// Offset in steps from the middle sample and value of the minimum, when
+    /// the middle sample is a local minimum, refined with a parabola. There is
+    /// none until three samples have been taken.
+    fn minimum(&self) -> Option<(f64, f64)> {
+        if self.count < 3 {
+            return None;
+        }
+        let [a, b, c] = self.values;
+        if !(b < a && b <= c) {
+            return None;
+        }
+        let curvature = a - 2.0 * b + c;
+        if curvature <= 0.0 {
+            return Some((0.0, b));
+        }
+        let offset = (a - c) / (2.0 * curvature);
+        Some((offset, b - (a - c).powi(2) / (8.0 * curvature)))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ApproachDetector {
+    last_time: f64,
+    step: f64,
+    distances: HashMap<(Entity, Entity), Samples>,
+    angles: HashMap<(Entity, Entity), Samples>,
+    observer: Option<Entity>,
+    /// State at the previous step, where minima are found
+    checkpoint: Option<Checkpoint>,
+}
+
+/// Records local minima of the distance between two bodies below
+/// `APPROACH_THRESHOLD`, and of their angular separation as seen from the
+/// conjunction observer below `CONJUNCTION_THRESHOLD`.
+///
+/// Satellites are left out of approaches, since moons of the same planet are
+/// always close, and a body is never paired with its own primary for them.
+#[allow(clippy::too_many_arguments)]
+pub fn detect_approaches(
+    clock: Res<SimClock>,
+    observer: Res<EventObserver>,
+    mut detector: ResMut<ApproachDetector>,
+    mut log: ResMut<EventLog>,
+    mut jumps: EventReader<ClockJumped>,
+    bodies: Query<Body>,
+    states: Query<(Entity, &Coord, &Velocity, &Trajectory)>,
+) {
+    if jumps.read().count() > 0 {
+        *detector = ApproachDetector {
+            last_time: clock.0,
+            ..default()
+        };
+        return;
+    }
+
+    let now = clock.0;
+    if now > detector.last_time {
+        detector.step = now - detector.last_time;
+    }
+    let step = detector.step;
+    let bodies: Vec<_> = bodies.iter().collect();
+    let observer = observer.conjunctions.or_else(|| {
+        bodies
+            .iter()
+            .find(|body| body.1.as_str() == "Earth")
+            .map(|body| body.0)
+    });
+    let observer = observer.and_then(|o| bodies.iter().find(|body| body.0 == o));
+    let observer_entity = observer.map(|body| body.0);
+    if detector.observer != observer_entity {
+        detector.observer = observer_entity;
+        detector.angles.clear();
+    }
+
+    let mut found = Vec::new();
+    for (i, a) in bodies.iter().enumerate() {
+        for b in &bodies[i + 1..] {
+            let key = (a.0, b.0);
+            let distance = a.2 .0.distance(b.2 .0);
+
+            if paired(a, b) {
+                let samples = detector.distances.entry(key).or_default();
+                samples.push(distance);
+                if let Some((offset, minimum)) = samples.minimum() {
+                    if minimum < APPROACH_THRESHOLD {
+                        found.push((
+                            "Close approach",
+                            key,
+                            offset,
+                            format!("{} - {}, {}", a.1, b.1, format_distance(minimum)),
+                        ));
+                    }
+                }
+            }
+
+            let Some(&(o, o_name, o_coord, ..)) = observer else {
+                continue;
+            };
+            if o == a.0 || o == b.0 || !visible_from(o, a) || !visible_from(o, b) {
+                continue;
+            }
+            let angle = (a.2 .0 - o_coord.0).angle_between(b.2 .0 - o_coord.0);
+            let samples = detector.angles.entry(key).or_default();
+            samples.push(angle);
+            if let Some((offset, minimum)) = samples.minimum() {
+                if minimum < CONJUNCTION_THRESHOLD {
+                    found.push((
+                        "Conjunction",
+                        key,
+                        offset,
+                        format!(
+                            "{} - {} from {}, {:.3} deg, {}",
+                            a.1,
+                            b.1,
+                            o_name,
+                            minimum.to_degrees(),
+                            format_distance(distance)
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    let checkpoint = Checkpoint::capture(now, &states);
+    if let Some(previous) = detector.checkpoint.replace(checkpoint) {
+        for (title, (a, b), offset, detail) in found {
+            let relative_velocity = match (previous.velocity(a), previous.velocity(b)) {
+                (Some(va), Some(vb)) => format!(", {:.3} km/s", (va - vb).length() / 1e3),
+                _ => String::new(),
+            };
+            log.record(LoggedEvent {
+                title: title.to_string(),
+                detail: detail + &relative_velocity,
+                start: None,
+                maximum: previous.time + offset * step,
+                end: None,
+                checkpoint: previous.clone(),
+            });
+        }
+    }
+
+    detector.last_time = now;
+}
+
+fn paired(a: &Body, b: &Body) -> bool {
+    let orbits = |body: &Body, other: Entity| body.5.is_some_and(|p| p.0 == other);
+    !orbits(a, b.0) && !orbits(b, a.0) && *a.4 != Kind::Satellite && *b.4 != Kind::Satellite
+}
+
+/// Satellites of other planets are too close to their planet to be told apart
+/// from it; only the observer's own satellites are considered.
+fn visible_from(observer: Entity, body: &Body) -> bool {
+    *body.4 != Kind::Satellite || body.5.is_some_and(|p| p.0 == observer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_minimum_before_three_samples() {
+        let mut samples = Samples::default();
+        samples.push(5.0);
+        assert_eq!(samples.minimum(), None);
+        samples.push(3.0);
+        assert_eq!(samples.minimum(), None);
+        samples.push(4.0);
+        let (offset, minimum) = samples.minimum().unwrap();
+        assert!(offset.abs() < 0.5);
+        assert!(minimum.is_finite() && minimum <= 3.0);
+    }
+}
@@ -0,0 +1,1018 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{constants::*, textures::Surface, Kind};
+
+/// Initial state and appearance of a body. Distance, velocity and
+/// inclination are relative to `primary` when one is given.
+pub struct BodySpec {
+    pub name: &'static str,
+    pub kind: Kind,
+    pub primary: Option<&'static str>,
+    pub mass: f64,
+    pub radius: f64,
+    pub distance: f64,
+    pub velocity: f32,
+    pub inclination: f32,
+    /// Angle between the rotation axis and the ecliptic normal
+    pub axial_tilt: f32,
+    /// Direction in the ecliptic plane towards which the axis leans
+    pub pole_azimuth: f32,
+    /// Sidereal rotation period in seconds
+    pub rotation_period: f64,
+    /// Effective temperature of a star in kelvin, which sets the color and
+    /// brightness of its light; unused for other bodies
+    pub temperature: f64,
+    /// Tint of the generated textures, multiplied for stars by the color of
+    /// their temperature
+    pub color: Color,
+    pub surface: Surface,
+    pub circle_size: f32,
+    pub label_shift: f32,
+    /// Camera distance in AU below which the label is shown
+    pub label_threshold: f64,
+}
+
+impl BodySpec {
+    /// Initial velocity, relative to the primary when one is given.
+    pub fn initial_velocity(&self) -> DVec3 {
+        (Quat::from_rotation_x(self.inclination) * Vec3::new(0.0, self.velocity, 0.0)).as_dvec3()
+    }
+}
+
+/// Bodies of the Solar System; primaries are listed before their satellites.
+pub const SOLAR_SYSTEM: &[BodySpec] = &[
+    BodySpec {
+        name: "Sun",
+        kind: Kind::Star,
+        primary: None,
+        mass: SUN_MASS,
+        radius: SUN_RADIUS,
+        distance: 0.0,
+        velocity: 0.0,
+        inclination: 0.0,
+        axial_tilt: SUN_AXIAL_TILT,
+        pole_azimuth: SUN_POLE_AZIMUTH,
+        rotation_period: SUN_ROTATION_PERIOD,
+        temperature: SUN_TEMPERATURE,
+        color: Color::ORANGE_RED,
+        surface: Surface::Star,
+        circle_size: 0.015,
+        label_shift: 6.0,
+        label_threshold: 150.0,
+    },
+    BodySpec {
+        name: "Mercury",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: MERCURY_MASS,
+        radius: MERCURY_RADIUS,
+        distance: MERCURY_DISTANCE,
+        velocity: MERCURY_VEL,
+        inclination: MERCURY_INCLINATION,
+        axial_tilt: MERCURY_AXIAL_TILT,
+        pole_azimuth: MERCURY_POLE_AZIMUTH,
+        rotation_period: MERCURY_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.5, 0.5, 0.5),
+        surface: Surface::Cratered,
+        circle_size: 0.01,
+        label_shift: 0.02,
+        label_threshold: 10.0,
+    },
+    BodySpec {
+        name: "Venus",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: VENUS_MASS,
+        radius: VENUS_RADIUS,
+        distance: VENUS_DISTANCE,
+        velocity: VENUS_VEL,
+        inclination: VENUS_INCLINATION,
+        axial_tilt: VENUS_AXIAL_TILT,
+        pole_azimuth: VENUS_POLE_AZIMUTH,
+        rotation_period: VENUS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.52, 0.0, 1.0),
+        surface: Surface::Rock,
+        circle_size: 0.01,
+        label_shift: 0.05,
+        label_threshold: 10.0,
+    },
+    BodySpec {
+        name: "Earth",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: EARTH_MASS,
+        radius: EARTH_RADIUS,
+        distance: EARTH_DISTANCE,
+        velocity: EARTH_VEL,
+        inclination: EARTH_INCLINATION,
+        axial_tilt: EARTH_AXIAL_TILT,
+        pole_azimuth: EARTH_POLE_AZIMUTH,
+        rotation_period: EARTH_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.2, 0.7, 0.2),
+        surface: Surface::Terrestrial,
+        circle_size: 0.01,
+        label_shift: 0.05,
+        label_threshold: 10.0,
+    },
+    BodySpec {
+        name: "Moon",
+        kind: Kind::Satellite,
+        primary: Some("Earth"),
+        mass: MOON_MASS,
+        radius: MOON_RADIUS,
+        distance: MOON_DISTANCE,
+        velocity: MOON_VEL,
+        inclination: MOON_INCLINATION,
+        axial_tilt: MOON_AXIAL_TILT,
+        pole_azimuth: MOON_POLE_AZIMUTH,
+        rotation_period: MOON_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.2, 0.2, 0.2),
+        surface: Surface::Cratered,
+        circle_size: 0.0075,
+        label_shift: 0.01,
+        label_threshold: 0.1,
+    },
+    BodySpec {
+        name: "Mars",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: MARS_MASS,
+        radius: MARS_RADIUS,
+        distance: MARS_DISTANCE,
+        velocity: MARS_VEL,
+        inclination: MARS_INCLINATION,
+        axial_tilt: MARS_AXIAL_TILT,
+        pole_azimuth: MARS_POLE_AZIMUTH,
+        rotation_period: MARS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.83, 0.35, 0.21),
+        surface: Surface::Rock,
+        circle_size: 0.01,
+        label_shift: 0.03,
+        label_threshold: 10.0,
+    },
+    BodySpec {
+        name: "Phobos",
+        kind: Kind::Satellite,
+        primary: Some("Mars"),
+        mass: PHOBOS_MASS,
+        radius: PHOBOS_RADIUS,
+        distance: PHOBOS_DISTANCE,
+        velocity: PHOBOS_VEL,
+        inclination: PHOBOS_INCLINATION,
+        axial_tilt: PHOBOS_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: PHOBOS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.4, 0.35, 0.3),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.0005,
+        label_threshold: 0.001,
+    },
+    BodySpec {
+        name: "Deimos",
+        kind: Kind::Satellite,
+        primary: Some("Mars"),
+        mass: DEIMOS_MASS,
+        radius: DEIMOS_RADIUS,
+        distance: DEIMOS_DISTANCE,
+        velocity: DEIMOS_VEL,
+        inclination: DEIMOS_INCLINATION,
+        axial_tilt: DEIMOS_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: DEIMOS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.55, 0.5, 0.45),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.0005,
+        label_threshold: 0.002,
+    },
+    BodySpec {
+        name: "Ceres",
+        kind: Kind::DwarfPlanet,
+        primary: Some("Sun"),
+        mass: CERES_MASS,
+        radius: CERES_RADIUS,
+        distance: CERES_DISTANCE,
+        velocity: CERES_VEL,
+        inclination: CERES_INCLINATION,
+        axial_tilt: CERES_AXIAL_TILT,
+        pole_azimuth: CERES_POLE_AZIMUTH,
+        rotation_period: CERES_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.7, 0.7, 0.7),
+        surface: Surface::Cratered,
+        circle_size: 0.01,
+        label_shift: 0.005,
+        label_threshold: 100.0,
+    },
+    BodySpec {
+        name: "Jupiter",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: JUPITER_MASS,
+        radius: JUPITER_RADIUS,
+        distance: JUPITER_DISTANCE,
+        velocity: JUPITER_VEL,
+        inclination: JUPITER_INCLINATION,
+        axial_tilt: JUPITER_AXIAL_TILT,
+        pole_azimuth: JUPITER_POLE_AZIMUTH,
+        rotation_period: JUPITER_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.76, 0.4, 0.11),
+        surface: Surface::GasGiant,
+        circle_size: 0.01,
+        label_shift: 0.7,
+        label_threshold: 100.0,
+    },
+    BodySpec {
+        name: "Io",
+        kind: Kind::Satellite,
+        primary: Some("Jupiter"),
+        mass: IO_MASS,
+        radius: IO_RADIUS,
+        distance: IO_DISTANCE,
+        velocity: IO_VEL,
+        inclination: IO_INCLINATION,
+        axial_tilt: IO_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: IO_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.9, 0.8, 0.3),
+        surface: Surface::Rock,
+        circle_size: 0.005,
+        label_shift: 0.02,
+        label_threshold: 0.05,
+    },
+    BodySpec {
+        name: "Europa",
+        kind: Kind::Satellite,
+        primary: Some("Jupiter"),
+        mass: EUROPA_MASS,
+        radius: EUROPA_RADIUS,
+        distance: EUROPA_DISTANCE,
+        velocity: EUROPA_VEL,
+        inclination: EUROPA_INCLINATION,
+        axial_tilt: EUROPA_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: EUROPA_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.8, 0.75, 0.65),
+        surface: Surface::Ice,
+        circle_size: 0.005,
+        label_shift: 0.02,
+        label_threshold: 0.05,
+    },
+    BodySpec {
+        name: "Ganymede",
+        kind: Kind::Satellite,
+        primary: Some("Jupiter"),
+        mass: GANYMEDE_MASS,
+        radius: GANYMEDE_RADIUS,
+        distance: GANYMEDE_DISTANCE,
+        velocity: GANYMEDE_VEL,
+        inclination: GANYMEDE_INCLINATION,
+        axial_tilt: GANYMEDE_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: GANYMEDE_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.55, 0.5, 0.45),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.03,
+        label_threshold: 0.05,
+    },
+    BodySpec {
+        name: "Callisto",
+        kind: Kind::Satellite,
+        primary: Some("Jupiter"),
+        mass: CALLISTO_MASS,
+        radius: CALLISTO_RADIUS,
+        distance: CALLISTO_DISTANCE,
+        velocity: CALLISTO_VEL,
+        inclination: CALLISTO_INCLINATION,
+        axial_tilt: CALLISTO_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: CALLISTO_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.4, 0.35, 0.3),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.03,
+        label_threshold: 0.05,
+    },
+    BodySpec {
+        name: "Saturn",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: SATURN_MASS,
+        radius: SATURN_RADIUS,
+        distance: SATURN_DISTANCE,
+        velocity: SATURN_VEL,
+        inclination: SATURN_INCLINATION,
+        axial_tilt: SATURN_AXIAL_TILT,
+        pole_azimuth: SATURN_POLE_AZIMUTH,
+        rotation_period: SATURN_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.53, 0.45, 0.28),
+        surface: Surface::GasGiant,
+        circle_size: 0.01,
+        label_shift: 0.6,
+        label_threshold: 100.0,
+    },
+    BodySpec {
+        name: "Mimas",
+        kind: Kind::Satellite,
+        primary: Some("Saturn"),
+        mass: MIMAS_MASS,
+        radius: MIMAS_RADIUS,
+        distance: MIMAS_DISTANCE,
+        velocity: MIMAS_VEL,
+        inclination: MIMAS_INCLINATION,
+        axial_tilt: MIMAS_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: MIMAS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.75, 0.75, 0.75),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.003,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Enceladus",
+        kind: Kind::Satellite,
+        primary: Some("Saturn"),
+        mass: ENCELADUS_MASS,
+        radius: ENCELADUS_RADIUS,
+        distance: ENCELADUS_DISTANCE,
+        velocity: ENCELADUS_VEL,
+        inclination: ENCELADUS_INCLINATION,
+        axial_tilt: ENCELADUS_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: ENCELADUS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.95, 0.95, 0.95),
+        surface: Surface::Ice,
+        circle_size: 0.005,
+        label_shift: 0.003,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Tethys",
+        kind: Kind::Satellite,
+        primary: Some("Saturn"),
+        mass: TETHYS_MASS,
+        radius: TETHYS_RADIUS,
+        distance: TETHYS_DISTANCE,
+        velocity: TETHYS_VEL,
+        inclination: TETHYS_INCLINATION,
+        axial_tilt: TETHYS_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: TETHYS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.85, 0.85, 0.8),
+        surface: Surface::Ice,
+        circle_size: 0.005,
+        label_shift: 0.006,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Dione",
+        kind: Kind::Satellite,
+        primary: Some("Saturn"),
+        mass: DIONE_MASS,
+        radius: DIONE_RADIUS,
+        distance: DIONE_DISTANCE,
+        velocity: DIONE_VEL,
+        inclination: DIONE_INCLINATION,
+        axial_tilt: DIONE_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: DIONE_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.8, 0.8, 0.78),
+        surface: Surface::Ice,
+        circle_size: 0.005,
+        label_shift: 0.006,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Rhea",
+        kind: Kind::Satellite,
+        primary: Some("Saturn"),
+        mass: RHEA_MASS,
+        radius: RHEA_RADIUS,
+        distance: RHEA_DISTANCE,
+        velocity: RHEA_VEL,
+        inclination: RHEA_INCLINATION,
+        axial_tilt: RHEA_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: RHEA_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.75, 0.73, 0.7),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.008,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Titan",
+        kind: Kind::Satellite,
+        primary: Some("Saturn"),
+        mass: TITAN_MASS,
+        radius: TITAN_RADIUS,
+        distance: TITAN_DISTANCE,
+        velocity: TITAN_VEL,
+        inclination: TITAN_INCLINATION,
+        axial_tilt: TITAN_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: TITAN_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.85, 0.6, 0.25),
+        surface: Surface::Rock,
+        circle_size: 0.005,
+        label_shift: 0.03,
+        label_threshold: 0.03,
+    },
+    BodySpec {
+        name: "Iapetus",
+        kind: Kind::Satellite,
+        primary: Some("Saturn"),
+        mass: IAPETUS_MASS,
+        radius: IAPETUS_RADIUS,
+        distance: IAPETUS_DISTANCE,
+        velocity: IAPETUS_VEL,
+        inclination: IAPETUS_INCLINATION,
+        axial_tilt: IAPETUS_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: IAPETUS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.6, 0.55, 0.5),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.008,
+        label_threshold: 0.08,
+    },
+    BodySpec {
+        name: "Uranus",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: URANUS_MASS,
+        radius: URANUS_RADIUS,
+        distance: URANUS_DISTANCE,
+        velocity: URANUS_VEL,
+        inclination: URANUS_INCLINATION,
+        axial_tilt: URANUS_AXIAL_TILT,
+        pole_azimuth: URANUS_POLE_AZIMUTH,
+        rotation_period: URANUS_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.0, 0.78, 0.78),
+        surface: Surface::GasGiant,
+        circle_size: 0.01,
+        label_shift: 0.5,
+        label_threshold: 100.0,
+    },
+    BodySpec {
+        name: "Miranda",
+        kind: Kind::Satellite,
+        primary: Some("Uranus"),
+        mass: MIRANDA_MASS,
+        radius: MIRANDA_RADIUS,
+        distance: MIRANDA_DISTANCE,
+        velocity: MIRANDA_VEL,
+        inclination: MIRANDA_INCLINATION,
+        axial_tilt: MIRANDA_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: MIRANDA_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.6, 0.6, 0.6),
+        surface: Surface::Ice,
+        circle_size: 0.005,
+        label_shift: 0.003,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Ariel",
+        kind: Kind::Satellite,
+        primary: Some("Uranus"),
+        mass: ARIEL_MASS,
+        radius: ARIEL_RADIUS,
+        distance: ARIEL_DISTANCE,
+        velocity: ARIEL_VEL,
+        inclination: ARIEL_INCLINATION,
+        axial_tilt: ARIEL_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: ARIEL_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.65, 0.65, 0.65),
+        surface: Surface::Ice,
+        circle_size: 0.005,
+        label_shift: 0.006,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Umbriel",
+        kind: Kind::Satellite,
+        primary: Some("Uranus"),
+        mass: UMBRIEL_MASS,
+        radius: UMBRIEL_RADIUS,
+        distance: UMBRIEL_DISTANCE,
+        velocity: UMBRIEL_VEL,
+        inclination: UMBRIEL_INCLINATION,
+        axial_tilt: UMBRIEL_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: UMBRIEL_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.4, 0.4, 0.4),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.006,
+        label_threshold: 0.01,
+    },
+    BodySpec {
+        name: "Titania",
+        kind: Kind::Satellite,
+        primary: Some("Uranus"),
+        mass: TITANIA_MASS,
+        radius: TITANIA_RADIUS,
+        distance: TITANIA_DISTANCE,
+        velocity: TITANIA_VEL,
+        inclination: TITANIA_INCLINATION,
+        axial_tilt: TITANIA_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: TITANIA_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.6, 0.57, 0.55),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.008,
+        label_threshold: 0.02,
+    },
+    BodySpec {
+        name: "Oberon",
+        kind: Kind::Satellite,
+        primary: Some("Uranus"),
+        mass: OBERON_MASS,
+        radius: OBERON_RADIUS,
+        distance: OBERON_DISTANCE,
+        velocity: OBERON_VEL,
+        inclination: OBERON_INCLINATION,
+        axial_tilt: OBERON_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: OBERON_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.55, 0.5, 0.48),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.008,
+        label_threshold: 0.02,
+    },
+    BodySpec {
+        name: "Neptune",
+        kind: Kind::Planet,
+        primary: Some("Sun"),
+        mass: NEPTUNE_MASS,
+        radius: NEPTUNE_RADIUS,
+        distance: NEPTUNE_DISTANCE,
+        velocity: NEPTUNE_VEL,
+        inclination: NEPTUNE_INCLINATION,
+        axial_tilt: NEPTUNE_AXIAL_TILT,
+        pole_azimuth: NEPTUNE_POLE_AZIMUTH,
+        rotation_period: NEPTUNE_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.02, 0.26, 0.82),
+        surface: Surface::GasGiant,
+        circle_size: 0.01,
+        label_shift: 0.5,
+        label_threshold: 100.0,
+    },
+    BodySpec {
+        name: "Triton",
+        kind: Kind::Satellite,
+        primary: Some("Neptune"),
+        mass: TRITON_MASS,
+        radius: TRITON_RADIUS,
+        distance: TRITON_DISTANCE,
+        velocity: TRITON_VEL,
+        inclination: TRITON_INCLINATION,
+        axial_tilt: TRITON_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: TRITON_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.8, 0.72, 0.7),
+        surface: Surface::Ice,
+        circle_size: 0.005,
+        label_shift: 0.015,
+        label_threshold: 0.015,
+    },
+    BodySpec {
+        name: "Pluto",
+        kind: Kind::DwarfPlanet,
+        primary: Some("Sun"),
+        mass: PLUTO_MASS,
+        radius: PLUTO_RADIUS,
+        distance: PLUTO_DISTANCE,
+        velocity: PLUTO_VEL,
+        inclination: PLUTO_INCLINATION,
+        axial_tilt: PLUTO_AXIAL_TILT,
+        pole_azimuth: PLUTO_POLE_AZIMUTH,
+        rotation_period: PLUTO_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.69, 0.55, 0.43),
+        surface: Surface::Ice,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 100.0,
+    },
+    BodySpec {
+        name: "Charon",
+        kind: Kind::Satellite,
+        primary: Some("Pluto"),
+        mass: CHARON_MASS,
+        radius: CHARON_RADIUS,
+        distance: CHARON_DISTANCE,
+        velocity: CHARON_VEL,
+        inclination: CHARON_INCLINATION,
+        axial_tilt: CHARON_INCLINATION,
+        pole_azimuth: 0.0,
+        rotation_period: CHARON_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.5, 0.48, 0.45),
+        surface: Surface::Cratered,
+        circle_size: 0.005,
+        label_shift: 0.005,
+        label_threshold: 0.001,
+    },
+    BodySpec {
+        name: "Halley",
+        kind: Kind::Comet,
+        primary: Some("Sun"),
+        mass: HALLEY_MASS,
+        radius: HALLEY_RADIUS,
+        distance: HALLEY_DISTANCE,
+        velocity: HALLEY_VEL,
+        inclination: HALLEY_INCLINATION,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: HALLEY_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.35, 0.33, 0.3),
+        surface: Surface::Rock,
+        circle_size: 0.008,
+        label_shift: 0.005,
+        label_threshold: 100.0,
+    },
+    BodySpec {
+        name: "Hale-Bopp",
+        kind: Kind::Comet,
+        primary: Some("Sun"),
+        mass: HALE_BOPP_MASS,
+        radius: HALE_BOPP_RADIUS,
+        distance: HALE_BOPP_DISTANCE,
+        velocity: HALE_BOPP_VEL,
+        inclination: HALE_BOPP_INCLINATION,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: HALE_BOPP_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.4, 0.38, 0.35),
+        surface: Surface::Rock,
+        circle_size: 0.008,
+        label_shift: 0.005,
+        label_threshold: 100.0,
+    },
+];
+
+/// Alpha Centauri A and B, with Proxima Centauri far out around them.
+pub const ALPHA_CENTAURI: &[BodySpec] = &[
+    BodySpec {
+        name: "Alpha Centauri A",
+        kind: Kind::Star,
+        primary: None,
+        mass: ALPHA_CEN_A_MASS,
+        radius: ALPHA_CEN_A_RADIUS,
+        distance: 0.0,
+        velocity: 0.0,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: ALPHA_CEN_A_ROTATION_PERIOD,
+        temperature: ALPHA_CEN_A_TEMPERATURE,
+        color: Color::WHITE,
+        surface: Surface::Star,
+        circle_size: 0.015,
+        label_shift: 6.0,
+        label_threshold: 50000.0,
+    },
+    BodySpec {
+        name: "Alpha Centauri B",
+        kind: Kind::Star,
+        primary: Some("Alpha Centauri A"),
+        mass: ALPHA_CEN_B_MASS,
+        radius: ALPHA_CEN_B_RADIUS,
+        distance: ALPHA_CEN_B_DISTANCE,
+        velocity: ALPHA_CEN_B_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: ALPHA_CEN_B_ROTATION_PERIOD,
+        temperature: ALPHA_CEN_B_TEMPERATURE,
+        color: Color::WHITE,
+        surface: Surface::Star,
+        circle_size: 0.015,
+        label_shift: 6.0,
+        label_threshold: 50000.0,
+    },
+    BodySpec {
+        name: "Proxima Centauri",
+        kind: Kind::Star,
+        primary: Some("Alpha Centauri A"),
+        mass: PROXIMA_MASS,
+        radius: PROXIMA_RADIUS,
+        distance: PROXIMA_DISTANCE,
+        velocity: PROXIMA_VEL,
+        inclination: PROXIMA_VEL_TILT,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: PROXIMA_ROTATION_PERIOD,
+        temperature: PROXIMA_TEMPERATURE,
+        color: Color::WHITE,
+        surface: Surface::Star,
+        circle_size: 0.015,
+        label_shift: 1.0,
+        label_threshold: 50000.0,
+    },
+    BodySpec {
+        name: "Proxima b",
+        kind: Kind::Planet,
+        primary: Some("Proxima Centauri"),
+        mass: PROXIMA_B_MASS,
+        radius: PROXIMA_B_RADIUS,
+        distance: PROXIMA_B_DISTANCE,
+        velocity: PROXIMA_B_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: PROXIMA_B_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.6, 0.45, 0.35),
+        surface: Surface::Rock,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+];
+
+/// Kepler-16, a close binary of two small stars circled by a Saturn-mass
+/// planet.
+pub const KEPLER_16: &[BodySpec] = &[
+    BodySpec {
+        name: "Kepler-16 A",
+        kind: Kind::Star,
+        primary: None,
+        mass: KEPLER_16_A_MASS,
+        radius: KEPLER_16_A_RADIUS,
+        distance: 0.0,
+        velocity: 0.0,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: KEPLER_16_A_ROTATION_PERIOD,
+        temperature: KEPLER_16_A_TEMPERATURE,
+        color: Color::WHITE,
+        surface: Surface::Star,
+        circle_size: 0.015,
+        label_shift: 4.0,
+        label_threshold: 150.0,
+    },
+    BodySpec {
+        name: "Kepler-16 B",
+        kind: Kind::Star,
+        primary: Some("Kepler-16 A"),
+        mass: KEPLER_16_B_MASS,
+        radius: KEPLER_16_B_RADIUS,
+        distance: KEPLER_16_B_DISTANCE,
+        velocity: KEPLER_16_B_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: KEPLER_16_B_ROTATION_PERIOD,
+        temperature: KEPLER_16_B_TEMPERATURE,
+        color: Color::WHITE,
+        surface: Surface::Star,
+        circle_size: 0.015,
+        label_shift: 1.5,
+        label_threshold: 150.0,
+    },
+    BodySpec {
+        name: "Kepler-16 b",
+        kind: Kind::Planet,
+        primary: Some("Kepler-16 A"),
+        mass: KEPLER_16_BB_MASS,
+        radius: KEPLER_16_BB_RADIUS,
+        distance: KEPLER_16_BB_DISTANCE,
+        velocity: KEPLER_16_BB_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: KEPLER_16_BB_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.75, 0.65, 0.5),
+        surface: Surface::GasGiant,
+        circle_size: 0.01,
+        label_shift: 0.4,
+        label_threshold: 10.0,
+    },
+];
+
+/// TRAPPIST-1, an ultracool dwarf with seven Earth-sized planets packed
+/// closer to it than Mercury is to the Sun.
+pub const TRAPPIST_1: &[BodySpec] = &[
+    BodySpec {
+        name: "TRAPPIST-1",
+        kind: Kind::Star,
+        primary: None,
+        mass: TRAPPIST_1_MASS,
+        radius: TRAPPIST_1_RADIUS,
+        distance: 0.0,
+        velocity: 0.0,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1_ROTATION_PERIOD,
+        temperature: TRAPPIST_1_TEMPERATURE,
+        color: Color::WHITE,
+        surface: Surface::Star,
+        circle_size: 0.015,
+        label_shift: 1.0,
+        label_threshold: 150.0,
+    },
+    BodySpec {
+        name: "TRAPPIST-1 b",
+        kind: Kind::Planet,
+        primary: Some("TRAPPIST-1"),
+        mass: TRAPPIST_1B_MASS,
+        radius: TRAPPIST_1B_RADIUS,
+        distance: TRAPPIST_1B_DISTANCE,
+        velocity: TRAPPIST_1B_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1B_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.55, 0.4, 0.35),
+        surface: Surface::Rock,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+    BodySpec {
+        name: "TRAPPIST-1 c",
+        kind: Kind::Planet,
+        primary: Some("TRAPPIST-1"),
+        mass: TRAPPIST_1C_MASS,
+        radius: TRAPPIST_1C_RADIUS,
+        distance: TRAPPIST_1C_DISTANCE,
+        velocity: TRAPPIST_1C_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1C_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.6, 0.5, 0.4),
+        surface: Surface::Rock,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+    BodySpec {
+        name: "TRAPPIST-1 d",
+        kind: Kind::Planet,
+        primary: Some("TRAPPIST-1"),
+        mass: TRAPPIST_1D_MASS,
+        radius: TRAPPIST_1D_RADIUS,
+        distance: TRAPPIST_1D_DISTANCE,
+        velocity: TRAPPIST_1D_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1D_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.5, 0.5, 0.55),
+        surface: Surface::Terrestrial,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+    BodySpec {
+        name: "TRAPPIST-1 e",
+        kind: Kind::Planet,
+        primary: Some("TRAPPIST-1"),
+        mass: TRAPPIST_1E_MASS,
+        radius: TRAPPIST_1E_RADIUS,
+        distance: TRAPPIST_1E_DISTANCE,
+        velocity: TRAPPIST_1E_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1E_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.45, 0.55, 0.6),
+        surface: Surface::Terrestrial,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+    BodySpec {
+        name: "TRAPPIST-1 f",
+        kind: Kind::Planet,
+        primary: Some("TRAPPIST-1"),
+        mass: TRAPPIST_1F_MASS,
+        radius: TRAPPIST_1F_RADIUS,
+        distance: TRAPPIST_1F_DISTANCE,
+        velocity: TRAPPIST_1F_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1F_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.8, 0.85, 0.9),
+        surface: Surface::Ice,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+    BodySpec {
+        name: "TRAPPIST-1 g",
+        kind: Kind::Planet,
+        primary: Some("TRAPPIST-1"),
+        mass: TRAPPIST_1G_MASS,
+        radius: TRAPPIST_1G_RADIUS,
+        distance: TRAPPIST_1G_DISTANCE,
+        velocity: TRAPPIST_1G_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1G_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.85, 0.9, 0.95),
+        surface: Surface::Ice,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+    BodySpec {
+        name: "TRAPPIST-1 h",
+        kind: Kind::Planet,
+        primary: Some("TRAPPIST-1"),
+        mass: TRAPPIST_1H_MASS,
+        radius: TRAPPIST_1H_RADIUS,
+        distance: TRAPPIST_1H_DISTANCE,
+        velocity: TRAPPIST_1H_VEL,
+        inclination: 0.0,
+        axial_tilt: 0.0,
+        pole_azimuth: 0.0,
+        rotation_period: TRAPPIST_1H_ROTATION_PERIOD,
+        temperature: 0.0,
+        color: Color::rgb(0.7, 0.75, 0.8),
+        surface: Surface::Ice,
+        circle_size: 0.01,
+        label_shift: 0.01,
+        label_threshold: 1.0,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxima_is_bound_to_alpha_centauri_a_and_b() {
+        let find = |name| {
+            ALPHA_CENTAURI
+                .iter()
+                .find(|spec| spec.name == name)
+                .unwrap()
+        };
+        let (a, b) = (find("Alpha Centauri A"), find("Alpha Centauri B"));
+        let proxima = find("Proxima Centauri");
+
+        let mass = a.mass + b.mass;
+        let barycenter = DVec3::X * b.distance * b.mass / mass;
+        let drift = b.initial_velocity() * b.mass / mass;
+        let position = DVec3::X * proxima.distance - barycenter;
+        let velocity = proxima.initial_velocity() - drift;
+
+        assert!((velocity.length() - PROXIMA_ORBITAL_VEL as f64).abs() < 0.1);
+        assert!((velocity.angle_between(DVec3::Y) - PROXIMA_INCLINATION as f64).abs() < 1e-3);
+        let energy =
+            velocity.length_squared() / 2.0 - G * (mass + proxima.mass) / position.length();
+        assert!(energy < 0.0, "{} J/kg", energy);
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    constants::*,
+    forces::{sublimation, NonGravitational, Outgassing},
+    lighting::Luminosity,
+    Coord, Kind, Star, Velocity,
+};
+
+/// Marsden's A1, A2 and A3 parameters in AU/day² of the comets whose
+/// outgassing has been measured.
+const OUTGASSING: &[(&str, [f64; 3])] = &[("Halley", HALLEY_OUTGASSING)];
+
+/// Tails stop growing this close to the star, relative to their length at
+/// 1 AU.
+const MAX_ACTIVITY: f64 = 4.0;
+/// Lines drawn across each tail, and points along each line.
+const STRANDS: usize = 7;
+const SEGMENTS: usize = 16;
+
+pub fn spawn_comet_outgassing(mut commands: Commands, comets: Query<(Entity, &Name, &Kind)>) {
+    for (entity, name, kind) in &comets {
+        if *kind != Kind::Comet {
+            continue;
+        }
+        if let Some(&(_, [a1, a2, a3])) = OUTGASSING
+            .iter()
+            .find(|outgassing| outgassing.0 == name.as_str())
+        {
+            commands
+                .entity(entity)
+                .insert(NonGravitational::new(Outgassing::from_au_per_day2(
+                    a1, a2, a3,
+                )));
+        }
+    }
+}
+
+/// Draws the ion tail of each comet, blown straight back by the solar wind
+/// of the nearest star, and its dust tail, curving behind the comet along
+/// its orbit. Both grow longer and brighter as the comet nears the star and
+/// its ice sublimates faster.
+pub fn draw_comet_tails(
+    mut gizmos: Gizmos,
+    comets: Query<(&Kind, &Coord, &Velocity)>,
+    stars: Query<(&Coord, &Velocity, &Luminosity), With<Star>>,
+) {
+    for (kind, coord, velocity) in &comets {
+        if *kind != Kind::Comet {
+            continue;
+        }
+        let Some((star_coord, star_velocity, luminosity)) = stars.iter().min_by(|a, b| {
+            let (da, db) = (a.0 .0.distance(coord.0), b.0 .0.distance(coord.0));
+            da.total_cmp(&db)
+        }) else {
+            continue;
+        };
+        let r = coord.0 - star_coord.0;
+        let v = velocity.0 - star_velocity.0;
+        let activity = sublimation(r.length() / luminosity.0.sqrt()).min(MAX_ACTIVITY);
+        let brightness = activity.min(1.0) as f32;
+        if brightness < 0.01 {
+            continue;
+        }
+
+        let nucleus = (coord.0 * SCALE).as_vec3();
+        let radial = r.normalize();
+        let normal = r.cross(v).normalize();
+        let transverse = normal.cross(radial);
+        let scene = |offset: DVec3| nucleus + (offset * SCALE).as_vec3();
+
+        // The ion tail trails the comet's motion through the solar wind
+        let ion = (radial * SOLAR_WIND_SPEED - v).normalize();
+        let ion_length = ION_TAIL_LENGTH * activity;
+        let across = ion.cross(normal);
+        let ion_color = Color::rgba(0.4, 0.6, 1.0, brightness);
+        for strand in 0..STRANDS {
+            let spread = strand as f64 / (STRANDS - 1) as f64 - 0.5;
+            let end = (ion + across * spread * 0.04) * ion_length;
+            gizmos.line_gradient(nucleus, scene(end), ion_color, ion_color.with_a(0.0));
+        }
+
+        // Dust released earlier lags further behind, fanning out in the
+        // orbit plane
+        let dust_length = DUST_TAIL_LENGTH * activity;
+        let dust_color = Color::rgba(1.0, 0.95, 0.8, brightness);
+        for strand in 0..STRANDS {
+            let spread = strand as f64 / (STRANDS - 1) as f64;
+            let points = (0..=SEGMENTS).map(|i| {
+                let s = i as f64 / SEGMENTS as f64;
+                let offset = radial * s - transverse * (0.2 + 0.4 * spread) * s * s;
+                let fade = 1.0 - s as f32;
+                (
+                    scene(offset * dust_length),
+                    dust_color.with_a(brightness * fade),
+                )
+            });
+            gizmos.linestrip_gradient(points);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{setup, tests::headless_app};
+
+    #[test]
+    fn startup_makes_halley_outgas() {
+        let mut app = headless_app();
+        app.add_systems(
+            Startup,
+            (setup, apply_deferred, spawn_comet_outgassing).chain(),
+        );
+        app.update();
+        let world = &mut app.world;
+        let halley = world
+            .query::<(&Name, Has<NonGravitational<Outgassing>>)>()
+            .iter(world)
+            .find(|(name, _)| name.as_str() == "Halley")
+            .map(|(_, outgassing)| outgassing);
+        assert_eq!(halley, Some(true));
+    }
+}
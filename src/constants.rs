@@ -1,8 +1,70 @@
+// Inclinations keep the digits they were converted from degrees with, more
+// than an f32 holds
+#![allow(clippy::excessive_precision)]
+
 pub const G: f64 = 6.67428 * 1e-11; // N * m2 * kg2
 pub const AU: f64 = 1.496 * 1e11; // m
 pub const SCALE: f64 = 2000.0 / AU;
 pub const TIMESTEP: f32 = 60.0 * 60.0; // * 24.0; //seconds in day
 
+// Phobos circles Mars in under 8 hours, so each timestep is integrated in substeps
+pub const SUBSTEPS: u32 = 30;
+pub const DT: f32 = TIMESTEP / SUBSTEPS as f32;
+// Largest number of timesteps simulated per fixed update, with patched conics
+// and with N-body integration
+pub const MAX_WARP: u32 = 1000;
+pub const MAX_N_BODY_WARP: u32 = 10;
+pub const SPEED_OF_LIGHT: f64 = 299_792_458.0; // m/s
+
+// Lighting
+// Point light power, in scene lumens, bright enough to light the Earth at 1 AU
+pub const SUN_LIGHT_INTENSITY: f32 = 8.0 * 1e8;
+pub const LIGHT_RANGE: f32 = (100.0 * AU * SCALE) as f32;
+// Below this distance from a star the exposure stops compensating
+pub const EXPOSURE_MIN_DISTANCE: f64 = 0.3 * AU;
+// Effective temperature of the Sun, K, which other stars' luminosity is relative to
+pub const SUN_TEMPERATURE: f64 = 5772.0;
+
+// Event detection
+pub const APPROACH_THRESHOLD: f64 = AU;
+pub const CONJUNCTION_THRESHOLD: f64 = 2.0 * std::f64::consts::PI / 180.0;
+
+// Lagrange points
+pub const TEST_PARTICLE_MASS: f64 = 1.0;
+
+// Spacecraft
+pub const STANDARD_GRAVITY: f64 = 9.80665; // m/s2
+pub const CRAFT_RADIUS: f64 = 10.0;
+pub const GEO_DISTANCE: f64 = 4.2164 * 1e7;
+pub const GEO_VEL: f32 = 3.0747 * 1e3;
+// Hill radii between a planet and a spacecraft launched from its orbit
+pub const TRANSFER_CLEARANCE: f64 = 10.0;
+
+// Non-gravitational forces
+// Pressure of sunlight on an absorbing surface at 1 AU, N/m2
+pub const SOLAR_RADIATION_PRESSURE: f64 = 4.56 * 1e-6;
+// Marsden's A1, A2 and A3 parameters of 1P/Halley, AU/day2
+pub const HALLEY_OUTGASSING: [f64; 3] = [0.041e-8, 0.0155e-8, 0.0];
+
+// Comet tails
+// Lengths at 1 AU from the star, m
+pub const ION_TAIL_LENGTH: f64 = 0.15 * AU;
+pub const DUST_TAIL_LENGTH: f64 = 0.08 * AU;
+pub const SOLAR_WIND_SPEED: f64 = 4.0 * 1e5; // m/s
+
+// Trajectory prediction, s
+pub const PREDICTION_HORIZON: f64 = 10.0 * 86400.0;
+pub const PREDICTION_REFRESH: f64 = 86400.0;
+pub const PREDICTION_STEP: f64 = 300.0;
+
+// Recording and export, s
+pub const RECORD_CADENCE: f64 = 3600.0;
+pub const EXPORT_CADENCE: f64 = 86400.0;
+
+// Integrator benchmarks, s
+pub const BENCHMARK_SPAN: f64 = 365.25 * 86400.0;
+pub const CENTURY: f64 = 100.0 * 365.25 * 86400.0;
+
 // Mass, kg
 pub const SUN_MASS: f64 = 1.98892 * 1e30;
 pub const MERCURY_MASS: f64 = 3.3011 * 1e23;
@@ -16,6 +78,28 @@ pub const SATURN_MASS: f64 = 5.6834 * 1e26;
 pub const URANUS_MASS: f64 = 8.6810 * 1e25;
 pub const NEPTUNE_MASS: f64 = 1.02413 * 1e26;
 pub const PLUTO_MASS: f64 = 1.303 * 1e22;
+pub const HALLEY_MASS: f64 = 2.2 * 1e14;
+pub const HALE_BOPP_MASS: f64 = 1.3 * 1e16;
+pub const PHOBOS_MASS: f64 = 1.0659 * 1e16;
+pub const DEIMOS_MASS: f64 = 1.4762 * 1e15;
+pub const IO_MASS: f64 = 8.931938 * 1e22;
+pub const EUROPA_MASS: f64 = 4.799844 * 1e22;
+pub const GANYMEDE_MASS: f64 = 1.4819 * 1e23;
+pub const CALLISTO_MASS: f64 = 1.075938 * 1e23;
+pub const MIMAS_MASS: f64 = 3.7493 * 1e19;
+pub const ENCELADUS_MASS: f64 = 1.08022 * 1e20;
+pub const TETHYS_MASS: f64 = 6.17449 * 1e20;
+pub const DIONE_MASS: f64 = 1.095452 * 1e21;
+pub const RHEA_MASS: f64 = 2.306518 * 1e21;
+pub const TITAN_MASS: f64 = 1.3452 * 1e23;
+pub const IAPETUS_MASS: f64 = 1.805635 * 1e21;
+pub const MIRANDA_MASS: f64 = 6.59 * 1e19;
+pub const ARIEL_MASS: f64 = 1.251 * 1e21;
+pub const UMBRIEL_MASS: f64 = 1.275 * 1e21;
+pub const TITANIA_MASS: f64 = 3.4 * 1e21;
+pub const OBERON_MASS: f64 = 3.076 * 1e21;
+pub const TRITON_MASS: f64 = 2.139 * 1e22;
+pub const CHARON_MASS: f64 = 1.586 * 1e21;
 
 // Velocity, m/s
 pub const MERCURY_VEL: f32 = 4.736 * 1e4;
@@ -29,6 +113,30 @@ pub const SATURN_VEL: f32 = 9.68 * 1e3;
 pub const URANUS_VEL: f32 = 6.8 * 1e3;
 pub const NEPTUNE_VEL: f32 = 5.43 * 1e3;
 pub const PLUTO_VEL: f32 = 4.743 * 1e3;
+pub const HALLEY_VEL: f32 = 5.4578 * 1e4;
+pub const HALE_BOPP_VEL: f32 = 4.401 * 1e4;
+
+// Satellite velocity relative to its planet, m/s
+pub const PHOBOS_VEL: f32 = 2.137 * 1e3;
+pub const DEIMOS_VEL: f32 = 1.351 * 1e3;
+pub const IO_VEL: f32 = 1.7333 * 1e4;
+pub const EUROPA_VEL: f32 = 1.3742 * 1e4;
+pub const GANYMEDE_VEL: f32 = 1.088 * 1e4;
+pub const CALLISTO_VEL: f32 = 8.203 * 1e3;
+pub const MIMAS_VEL: f32 = 1.4299 * 1e4;
+pub const ENCELADUS_VEL: f32 = 1.2624 * 1e4;
+pub const TETHYS_VEL: f32 = 1.1346 * 1e4;
+pub const DIONE_VEL: f32 = 1.0025 * 1e4;
+pub const RHEA_VEL: f32 = 8.484 * 1e3;
+pub const TITAN_VEL: f32 = 5.572 * 1e3;
+pub const IAPETUS_VEL: f32 = 3.264 * 1e3;
+pub const MIRANDA_VEL: f32 = 6.692 * 1e3;
+pub const ARIEL_VEL: f32 = 5.508 * 1e3;
+pub const UMBRIEL_VEL: f32 = 4.665 * 1e3;
+pub const TITANIA_VEL: f32 = 3.646 * 1e3;
+pub const OBERON_VEL: f32 = 3.151 * 1e3;
+pub const TRITON_VEL: f32 = 4.39 * 1e3;
+pub const CHARON_VEL: f32 = 2.231 * 1e2;
 
 // Rdius, m
 pub const SUN_RADIUS: f64 = 6.957 * 1e8;
@@ -43,6 +151,28 @@ pub const SATURN_RADIUS: f64 = 5.8232 * 1e7;
 pub const URANUS_RADIUS: f64 = 2.5362 * 1e7;
 pub const NEPTUNE_RADIUS: f64 = 2.4622 * 1e7;
 pub const PLUTO_RADIUS: f64 = 1.1883 * 1e6;
+pub const HALLEY_RADIUS: f64 = 5.5 * 1e3;
+pub const HALE_BOPP_RADIUS: f64 = 3.0 * 1e4;
+pub const PHOBOS_RADIUS: f64 = 1.1267 * 1e4;
+pub const DEIMOS_RADIUS: f64 = 6.2 * 1e3;
+pub const IO_RADIUS: f64 = 1.8216 * 1e6;
+pub const EUROPA_RADIUS: f64 = 1.5608 * 1e6;
+pub const GANYMEDE_RADIUS: f64 = 2.6341 * 1e6;
+pub const CALLISTO_RADIUS: f64 = 2.4103 * 1e6;
+pub const MIMAS_RADIUS: f64 = 1.982 * 1e5;
+pub const ENCELADUS_RADIUS: f64 = 2.521 * 1e5;
+pub const TETHYS_RADIUS: f64 = 5.311 * 1e5;
+pub const DIONE_RADIUS: f64 = 5.614 * 1e5;
+pub const RHEA_RADIUS: f64 = 7.638 * 1e5;
+pub const TITAN_RADIUS: f64 = 2.57473 * 1e6;
+pub const IAPETUS_RADIUS: f64 = 7.345 * 1e5;
+pub const MIRANDA_RADIUS: f64 = 2.357 * 1e5;
+pub const ARIEL_RADIUS: f64 = 5.789 * 1e5;
+pub const UMBRIEL_RADIUS: f64 = 5.847 * 1e5;
+pub const TITANIA_RADIUS: f64 = 7.884 * 1e5;
+pub const OBERON_RADIUS: f64 = 7.614 * 1e5;
+pub const TRITON_RADIUS: f64 = 1.3534 * 1e6;
+pub const CHARON_RADIUS: f64 = 6.06 * 1e5;
 
 // Distance, m
 pub const MERCURY_DISTANCE: f64 = 0.387 * AU;
@@ -56,6 +186,31 @@ pub const SATURN_DISTANCE: f64 = 9.0 * AU;
 pub const URANUS_DISTANCE: f64 = 20.0 * AU;
 pub const NEPTUNE_DISTANCE: f64 = 30.1 * AU;
 pub const PLUTO_DISTANCE: f64 = 35.0 * AU;
+// Comets start at perihelion
+pub const HALLEY_DISTANCE: f64 = 0.586 * AU;
+pub const HALE_BOPP_DISTANCE: f64 = 0.914 * AU;
+
+// Satellite distance from its planet, m
+pub const PHOBOS_DISTANCE: f64 = 9.376 * 1e6;
+pub const DEIMOS_DISTANCE: f64 = 2.3463 * 1e7;
+pub const IO_DISTANCE: f64 = 4.217 * 1e8;
+pub const EUROPA_DISTANCE: f64 = 6.709 * 1e8;
+pub const GANYMEDE_DISTANCE: f64 = 1.0704 * 1e9;
+pub const CALLISTO_DISTANCE: f64 = 1.8827 * 1e9;
+pub const MIMAS_DISTANCE: f64 = 1.8552 * 1e8;
+pub const ENCELADUS_DISTANCE: f64 = 2.3802 * 1e8;
+pub const TETHYS_DISTANCE: f64 = 2.9466 * 1e8;
+pub const DIONE_DISTANCE: f64 = 3.774 * 1e8;
+pub const RHEA_DISTANCE: f64 = 5.2704 * 1e8;
+pub const TITAN_DISTANCE: f64 = 1.22187 * 1e9;
+pub const IAPETUS_DISTANCE: f64 = 3.5608 * 1e9;
+pub const MIRANDA_DISTANCE: f64 = 1.2939 * 1e8;
+pub const ARIEL_DISTANCE: f64 = 1.9102 * 1e8;
+pub const UMBRIEL_DISTANCE: f64 = 2.663 * 1e8;
+pub const TITANIA_DISTANCE: f64 = 4.3591 * 1e8;
+pub const OBERON_DISTANCE: f64 = 5.8352 * 1e8;
+pub const TRITON_DISTANCE: f64 = 3.54759 * 1e8;
+pub const CHARON_DISTANCE: f64 = 1.9596 * 1e7;
 
 // Inclination, radians
 pub const MERCURY_INCLINATION: f32 = 0.05899212871740834;
@@ -69,3 +224,192 @@ pub const SATURN_INCLINATION: f32 = 0.09616764178488756;
 pub const URANUS_INCLINATION: f32 = 0.11309733552923257;
 pub const NEPTUNE_INCLINATION: f32 = 0.11222467090323539;
 pub const PLUTO_INCLINATION: f32 = 0.20734511513692636;
+pub const HALLEY_INCLINATION: f32 = 2.83197;
+pub const HALE_BOPP_INCLINATION: f32 = 1.56085;
+
+// Satellite inclination to the ecliptic, radians
+pub const PHOBOS_INCLINATION: f32 = 0.4555;
+pub const DEIMOS_INCLINATION: f32 = 0.4712;
+pub const IO_INCLINATION: f32 = 0.0386;
+pub const EUROPA_INCLINATION: f32 = 0.0443;
+pub const GANYMEDE_INCLINATION: f32 = 0.0358;
+pub const CALLISTO_INCLINATION: f32 = 0.0351;
+pub const MIMAS_INCLINATION: f32 = 0.517;
+pub const ENCELADUS_INCLINATION: f32 = 0.49;
+pub const TETHYS_INCLINATION: f32 = 0.499;
+pub const DIONE_INCLINATION: f32 = 0.4896;
+pub const RHEA_INCLINATION: f32 = 0.4902;
+pub const TITAN_INCLINATION: f32 = 0.4904;
+pub const IAPETUS_INCLINATION: f32 = 0.2705;
+pub const MIRANDA_INCLINATION: f32 = 1.7821;
+pub const ARIEL_INCLINATION: f32 = 1.7069;
+pub const UMBRIEL_INCLINATION: f32 = 1.7078;
+pub const TITANIA_INCLINATION: f32 = 1.7078;
+pub const OBERON_INCLINATION: f32 = 1.7071;
+pub const TRITON_INCLINATION: f32 = 2.2619;
+pub const CHARON_INCLINATION: f32 = 1.9705;
+
+// Axial tilt to the ecliptic, radians
+pub const SUN_AXIAL_TILT: f32 = 0.12654;
+pub const MERCURY_AXIAL_TILT: f32 = 0.0006;
+pub const VENUS_AXIAL_TILT: f32 = 3.09551;
+pub const EARTH_AXIAL_TILT: f32 = 0.40911;
+pub const MARS_AXIAL_TILT: f32 = 0.43965;
+pub const CERES_AXIAL_TILT: f32 = 0.06981;
+pub const JUPITER_AXIAL_TILT: f32 = 0.05463;
+pub const SATURN_AXIAL_TILT: f32 = 0.46653;
+pub const URANUS_AXIAL_TILT: f32 = 1.70641;
+pub const NEPTUNE_AXIAL_TILT: f32 = 0.49428;
+pub const PLUTO_AXIAL_TILT: f32 = 2.13855;
+pub const MOON_AXIAL_TILT: f32 = 0.02688;
+
+// Pole azimuth in the ecliptic plane, radians, from the IAU pole right
+// ascension and declination. Satellites keep 0, which lines their axes up with
+// their orbit normals; the Moon's axis leans the other way, across the
+// ecliptic pole from its orbit normal
+pub const SUN_POLE_AZIMUTH: f32 = 1.32236;
+pub const MERCURY_POLE_AZIMUTH: f32 = 0.84186;
+pub const VENUS_POLE_AZIMUTH: f32 = 5.23924;
+pub const EARTH_POLE_AZIMUTH: f32 = std::f32::consts::PI;
+pub const MARS_POLE_AZIMUTH: f32 = 1.48078;
+pub const CERES_POLE_AZIMUTH: f32 = 1.76640;
+pub const JUPITER_POLE_AZIMUTH: f32 = 5.89615;
+pub const SATURN_POLE_AZIMUTH: f32 = 2.95881;
+pub const URANUS_POLE_AZIMUTH: f32 = 2.92599;
+pub const NEPTUNE_POLE_AZIMUTH: f32 = 0.85931;
+pub const PLUTO_POLE_AZIMUTH: f32 = 3.96802;
+pub const MOON_POLE_AZIMUTH: f32 = std::f32::consts::PI;
+
+// Sidereal rotation period, s. Satellites rotate synchronously with their orbit
+pub const SUN_ROTATION_PERIOD: f64 = 2192832.0;
+pub const MERCURY_ROTATION_PERIOD: f64 = 5067014.0;
+pub const VENUS_ROTATION_PERIOD: f64 = 20997360.0;
+pub const EARTH_ROTATION_PERIOD: f64 = 86164.1;
+pub const MOON_ROTATION_PERIOD: f64 = 2360591.0;
+pub const MARS_ROTATION_PERIOD: f64 = 88642.66;
+pub const CERES_ROTATION_PERIOD: f64 = 32667.0;
+pub const JUPITER_ROTATION_PERIOD: f64 = 35730.0;
+pub const SATURN_ROTATION_PERIOD: f64 = 38018.0;
+pub const URANUS_ROTATION_PERIOD: f64 = 62064.0;
+pub const NEPTUNE_ROTATION_PERIOD: f64 = 57996.0;
+pub const PLUTO_ROTATION_PERIOD: f64 = 551857.0;
+pub const HALLEY_ROTATION_PERIOD: f64 = 190080.0;
+pub const HALE_BOPP_ROTATION_PERIOD: f64 = 40860.0;
+pub const PHOBOS_ROTATION_PERIOD: f64 = 27554.0;
+pub const DEIMOS_ROTATION_PERIOD: f64 = 109075.0;
+pub const IO_ROTATION_PERIOD: f64 = 152854.0;
+pub const EUROPA_ROTATION_PERIOD: f64 = 306822.0;
+pub const GANYMEDE_ROTATION_PERIOD: f64 = 618153.0;
+pub const CALLISTO_ROTATION_PERIOD: f64 = 1441931.0;
+pub const MIMAS_ROTATION_PERIOD: f64 = 81386.0;
+pub const ENCELADUS_ROTATION_PERIOD: f64 = 118387.0;
+pub const TETHYS_ROTATION_PERIOD: f64 = 163106.0;
+pub const DIONE_ROTATION_PERIOD: f64 = 236469.0;
+pub const RHEA_ROTATION_PERIOD: f64 = 390700.0;
+pub const TITAN_ROTATION_PERIOD: f64 = 1377648.0;
+pub const IAPETUS_ROTATION_PERIOD: f64 = 6853734.0;
+pub const MIRANDA_ROTATION_PERIOD: f64 = 122123.0;
+pub const ARIEL_ROTATION_PERIOD: f64 = 217728.0;
+pub const UMBRIEL_ROTATION_PERIOD: f64 = 358272.0;
+pub const TITANIA_ROTATION_PERIOD: f64 = 752218.0;
+pub const OBERON_ROTATION_PERIOD: f64 = 1163874.0;
+pub const TRITON_ROTATION_PERIOD: f64 = 507773.0;
+pub const CHARON_ROTATION_PERIOD: f64 = 551857.0;
+
+// Other star systems, each laid out in the orbital plane of its innermost
+// stars. Companion stars start at periastron; planets circling a binary are
+// given relative to its heavier star, including the motion of the pair's
+// barycenter
+// Effective temperature, K
+pub const ALPHA_CEN_A_TEMPERATURE: f64 = 5790.0;
+pub const ALPHA_CEN_B_TEMPERATURE: f64 = 5260.0;
+pub const PROXIMA_TEMPERATURE: f64 = 3042.0;
+pub const KEPLER_16_A_TEMPERATURE: f64 = 4450.0;
+pub const KEPLER_16_B_TEMPERATURE: f64 = 3311.0;
+pub const TRAPPIST_1_TEMPERATURE: f64 = 2566.0;
+
+// Alpha Centauri; Proxima is near apastron of its 8700 AU orbit
+pub const ALPHA_CEN_A_MASS: f64 = 1.0788 * SUN_MASS;
+pub const ALPHA_CEN_B_MASS: f64 = 0.9092 * SUN_MASS;
+pub const PROXIMA_MASS: f64 = 0.1221 * SUN_MASS;
+pub const PROXIMA_B_MASS: f64 = 1.07 * EARTH_MASS;
+pub const ALPHA_CEN_A_RADIUS: f64 = 1.2175 * SUN_RADIUS;
+pub const ALPHA_CEN_B_RADIUS: f64 = 0.8591 * SUN_RADIUS;
+pub const PROXIMA_RADIUS: f64 = 0.1542 * SUN_RADIUS;
+pub const PROXIMA_B_RADIUS: f64 = 1.1 * EARTH_RADIUS;
+pub const ALPHA_CEN_B_DISTANCE: f64 = 11.198 * AU;
+pub const PROXIMA_DISTANCE: f64 = 13005.1 * AU;
+pub const PROXIMA_B_DISTANCE: f64 = 0.04857 * AU;
+pub const ALPHA_CEN_B_VEL: f32 = 1.5471 * 1e4;
+pub const PROXIMA_B_VEL: f32 = 4.7231 * 1e4;
+// Proxima moves around the barycenter of A and B in an orbit inclined to
+// theirs by the mutual inclination found from both orbits' inclination and
+// node on the sky (Kervella et al. 2017). Relative to A, it also moves with
+// that barycenter in the plane of A and B, at ALPHA_CEN_B_VEL * B / (A + B);
+// the sum is PROXIMA_VEL, tilted out of the plane by PROXIMA_VEL_TILT. The
+// orientation of Proxima b's orbit is unknown; it is left in the plane of A
+// and B
+pub const PROXIMA_ORBITAL_VEL: f32 = 269.93;
+pub const PROXIMA_INCLINATION: f32 = 1.44849;
+pub const PROXIMA_VEL: f32 = 7113.55;
+pub const PROXIMA_VEL_TILT: f32 = 0.037671;
+pub const ALPHA_CEN_A_ROTATION_PERIOD: f64 = 1900800.0;
+pub const ALPHA_CEN_B_ROTATION_PERIOD: f64 = 3127680.0;
+pub const PROXIMA_ROTATION_PERIOD: f64 = 7136640.0;
+pub const PROXIMA_B_ROTATION_PERIOD: f64 = 966816.0;
+
+// Kepler-16; the rotation of B is unmeasured and taken as synchronous with
+// the binary, that of b as Saturn's
+pub const KEPLER_16_A_MASS: f64 = 0.6897 * SUN_MASS;
+pub const KEPLER_16_B_MASS: f64 = 0.20255 * SUN_MASS;
+pub const KEPLER_16_BB_MASS: f64 = 0.333 * JUPITER_MASS;
+pub const KEPLER_16_A_RADIUS: f64 = 0.6489 * SUN_RADIUS;
+pub const KEPLER_16_B_RADIUS: f64 = 0.22623 * SUN_RADIUS;
+pub const KEPLER_16_BB_RADIUS: f64 = 5.389 * 1e7;
+pub const KEPLER_16_B_DISTANCE: f64 = 0.18855 * AU;
+pub const KEPLER_16_BB_DISTANCE: f64 = 0.7476 * AU;
+pub const KEPLER_16_B_VEL: f32 = 6.9776 * 1e4;
+pub const KEPLER_16_BB_VEL: f32 = 4.9362 * 1e4;
+pub const KEPLER_16_A_ROTATION_PERIOD: f64 = 3032640.0;
+pub const KEPLER_16_B_ROTATION_PERIOD: f64 = 3549226.0;
+pub const KEPLER_16_BB_ROTATION_PERIOD: f64 = SATURN_ROTATION_PERIOD;
+
+// TRAPPIST-1; the planets rotate synchronously with their orbits
+pub const TRAPPIST_1_MASS: f64 = 0.0898 * SUN_MASS;
+pub const TRAPPIST_1_RADIUS: f64 = 0.1192 * SUN_RADIUS;
+pub const TRAPPIST_1_ROTATION_PERIOD: f64 = 285120.0;
+pub const TRAPPIST_1B_MASS: f64 = 1.374 * EARTH_MASS;
+pub const TRAPPIST_1C_MASS: f64 = 1.308 * EARTH_MASS;
+pub const TRAPPIST_1D_MASS: f64 = 0.388 * EARTH_MASS;
+pub const TRAPPIST_1E_MASS: f64 = 0.692 * EARTH_MASS;
+pub const TRAPPIST_1F_MASS: f64 = 1.039 * EARTH_MASS;
+pub const TRAPPIST_1G_MASS: f64 = 1.321 * EARTH_MASS;
+pub const TRAPPIST_1H_MASS: f64 = 0.326 * EARTH_MASS;
+pub const TRAPPIST_1B_RADIUS: f64 = 1.116 * EARTH_RADIUS;
+pub const TRAPPIST_1C_RADIUS: f64 = 1.097 * EARTH_RADIUS;
+pub const TRAPPIST_1D_RADIUS: f64 = 0.788 * EARTH_RADIUS;
+pub const TRAPPIST_1E_RADIUS: f64 = 0.92 * EARTH_RADIUS;
+pub const TRAPPIST_1F_RADIUS: f64 = 1.045 * EARTH_RADIUS;
+pub const TRAPPIST_1G_RADIUS: f64 = 1.129 * EARTH_RADIUS;
+pub const TRAPPIST_1H_RADIUS: f64 = 0.755 * EARTH_RADIUS;
+pub const TRAPPIST_1B_DISTANCE: f64 = 0.01154 * AU;
+pub const TRAPPIST_1C_DISTANCE: f64 = 0.0158 * AU;
+pub const TRAPPIST_1D_DISTANCE: f64 = 0.02227 * AU;
+pub const TRAPPIST_1E_DISTANCE: f64 = 0.02925 * AU;
+pub const TRAPPIST_1F_DISTANCE: f64 = 0.03849 * AU;
+pub const TRAPPIST_1G_DISTANCE: f64 = 0.04683 * AU;
+pub const TRAPPIST_1H_DISTANCE: f64 = 0.06189 * AU;
+pub const TRAPPIST_1B_VEL: f32 = 8.3098 * 1e4;
+pub const TRAPPIST_1C_VEL: f32 = 7.1017 * 1e4;
+pub const TRAPPIST_1D_VEL: f32 = 5.9817 * 1e4;
+pub const TRAPPIST_1E_VEL: f32 = 5.2195 * 1e4;
+pub const TRAPPIST_1F_VEL: f32 = 4.5501 * 1e4;
+pub const TRAPPIST_1G_VEL: f32 = 4.1251 * 1e4;
+pub const TRAPPIST_1H_VEL: f32 = 3.5882 * 1e4;
+pub const TRAPPIST_1B_ROTATION_PERIOD: f64 = 130535.0;
+pub const TRAPPIST_1C_ROTATION_PERIOD: f64 = 209124.0;
+pub const TRAPPIST_1D_ROTATION_PERIOD: f64 = 349950.0;
+pub const TRAPPIST_1E_ROTATION_PERIOD: f64 = 526759.0;
+pub const TRAPPIST_1F_ROTATION_PERIOD: f64 = 795138.0;
+pub const TRAPPIST_1G_ROTATION_PERIOD: f64 = 1067099.0;
+pub const TRAPPIST_1H_ROTATION_PERIOD: f64 = 1621275.0;
@@ -0,0 +1,460 @@
+use std::{f64::consts::PI, fs::OpenOptions, io::Write};
+
+use bevy::{
+    math::DVec3,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    constants::*, format_time, patched::Propagation, Coord, Mass, Ordinal, Radius, SimClock, Star,
+    Trajectory, Velocity,
+};
+
+/// Completed events are appended here, one tab-separated line each.
+pub const EVENT_LOG_FILE: &str = "events.log";
+
+/// Number of log entries shown in the panel at once.
+const PANEL_ROWS: usize = 12;
+
+/// How much faster than at the start of a fixed step the direction to a body
+/// is allowed to turn during it, since bodies curve and come closer.
+const TURN_ALLOWANCE: f64 = 2.0;
+
+/// Sent after the state of all bodies has been replaced, so detectors can
+/// discard what they tracked on the abandoned timeline.
+#[derive(Event)]
+pub struct ClockJumped;
+
+/// State of every body at one instant.
+#[derive(Clone)]
+pub struct Checkpoint {
+    pub time: f64,
+    bodies: Vec<(Entity, DVec3, DVec3, usize)>,
+}
+
+impl Checkpoint {
+    pub fn capture(time: f64, bodies: &Query<(Entity, &Coord, &Velocity, &Trajectory)>) -> Self {
+        Checkpoint {
+            time,
+            bodies: bodies
+                .iter()
+                .map(|(entity, coord, velocity, trajectory)| {
+                    (entity, coord.0, velocity.0, trajectory.0.len())
+                })
+                .collect(),
+        }
+    }
+
+    pub fn velocity(&self, entity: Entity) -> Option<DVec3> {
+        self.bodies
+            .iter()
+            .find(|body| body.0 == entity)
+            .map(|body| body.2)
+    }
+}
+
+pub struct LoggedEvent {
+    pub title: String,
+    pub detail: String,
+    pub start: Option<f64>,
+    pub maximum: f64,
+    pub end: Option<f64>,
+    pub checkpoint: Checkpoint,
+}
+
+#[derive(Resource, Default)]
+pub struct EventLog {
+    pub events: Vec<LoggedEvent>,
+    pub selected: usize,
+    pub visible: bool,
+}
+
+impl EventLog {
+    pub fn record(&mut self, event: LoggedEvent) {
+        let time = |t: Option<f64>| t.map(format_time).unwrap_or_default();
+        let line = format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            time(event.start),
+            format_time(event.maximum),
+            time(event.end),
+            event.title,
+            event.detail
+        );
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(EVENT_LOG_FILE)
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+        if let Err(err) = written {
+            warn!("Could not write to {}: {}", EVENT_LOG_FILE, err);
+        }
+
+        self.events.push(event);
+        self.selected = self.events.len() - 1;
+    }
+}
+
+/// Bodies events are seen from: eclipses from every body when `None`,
+/// conjunctions from the Earth when `None`.
+#[derive(Resource, Default)]
+pub struct EventObserver {
+    pub eclipses: Option<Entity>,
+    pub conjunctions: Option<Entity>,
+}
+
+#[derive(Component)]
+pub struct EventPanel;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum EclipseKind {
+    Eclipse,
+    Transit,
+    Occultation,
+}
+
+/// Part of the occulter's shadow the observer is in.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Geometry {
+    Penumbra,
+    Antumbra,
+    Umbra,
+}
+
+/// Observer, occulter and target.
+type Alignment = (Entity, Entity, Entity);
+
+struct Overlap {
+    kind: EclipseKind,
+    start: f64,
+    maximum: f64,
+    magnitude: f64,
+    geometry: Geometry,
+    margin: f64,
+    checkpoint: Checkpoint,
+}
+
+#[derive(Resource, Default)]
+pub struct EclipseDetector {
+    last_time: f64,
+    /// Angular margin of alignments that came close on the previous step
+    near: HashMap<Alignment, f64>,
+    active: HashMap<Alignment, Overlap>,
+    /// Alignments that can come to overlap before the next fixed step
+    candidates: Vec<Alignment>,
+}
+
+/// Looks once per fixed step, from each observer, for the pairs of bodies
+/// whose discs could meet within the timesteps it warps over, given how fast
+/// their directions turn; only those are checked every timestep.
+pub fn select_eclipse_candidates(
+    observer: Res<EventObserver>,
+    propagation: Res<Propagation>,
+    mut detector: ResMut<EclipseDetector>,
+    bodies: Query<(Entity, &Coord, &Velocity, &Radius)>,
+) {
+    let window = propagation.warp as f64 * TIMESTEP as f64;
+    let bodies: Vec<_> = bodies.iter().collect();
+    let mut candidates = Vec::new();
+
+    for &(o, o_coord, o_velocity, _) in &bodies {
+        if observer.eclipses.is_some_and(|observer| observer != o) {
+            continue;
+        }
+
+        // Direction, angular radius and the most the direction can turn
+        let views: Vec<_> = bodies
+            .iter()
+            .filter(|body| body.0 != o)
+            .map(|(entity, coord, velocity, radius)| {
+                let diff = coord.0 - o_coord.0;
+                let distance = diff.length();
+                let turn = (velocity.0 - o_velocity.0).length() / distance * window;
+                let angular_radius = (radius.0 / distance).min(1.0).asin();
+                (*entity, diff / distance, angular_radius, turn)
+            })
+            .collect();
+
+        for (i, &(a, a_dir, a_radius, a_turn)) in views.iter().enumerate() {
+            for &(b, b_dir, b_radius, b_turn) in &views[i + 1..] {
+                let reach = a_radius + b_radius + TURN_ALLOWANCE * (a_turn + b_turn);
+                if a_dir.angle_between(b_dir) < reach {
+                    // Either may end up in front
+                    candidates.extend([(o, a, b), (o, b, a)]);
+                }
+            }
+        }
+    }
+
+    detector.candidates = candidates;
+}
+
+/// Looks for discs overlapping among the candidate alignments: a star covered
+/// by a comparable body is an eclipse, by a much smaller one a transit, and
+/// any other body covered is an occultation. Start and end are interpolated
+/// between steps; the maximum is the step with the largest magnitude.
+pub fn detect_eclipses(
+    clock: Res<SimClock>,
+    mut detector: ResMut<EclipseDetector>,
+    mut log: ResMut<EventLog>,
+    mut jumps: EventReader<ClockJumped>,
+    bodies: Query<(Entity, &Name, &Coord, &Radius, Has<Star>)>,
+    states: Query<(Entity, &Coord, &Velocity, &Trajectory)>,
+) {
+    if jumps.read().count() > 0 {
+        *detector = EclipseDetector {
+            last_time: clock.0,
+            candidates: std::mem::take(&mut detector.candidates),
+            ..default()
+        };
+        return;
+    }
+
+    let now = clock.0;
+    let last_time = detector.last_time;
+    let bodies: Vec<_> = bodies.iter().collect();
+    let positions: HashMap<_, _> = bodies
+        .iter()
+        .map(|&(entity, _, coord, radius, star)| (entity, (coord.0, radius.0, star)))
+        .collect();
+    let mut near = HashMap::new();
+    let mut overlapping = HashSet::new();
+
+    let candidates = std::mem::take(&mut detector.candidates);
+    for &key in &candidates {
+        let (o, c, t) = key;
+        let (Some(&(o_coord, ..)), Some(&(c_coord, c_size, _)), Some(&(t_coord, t_size, t_star))) =
+            (positions.get(&o), positions.get(&c), positions.get(&t))
+        else {
+            continue;
+        };
+
+        // Direction, distance and angular radius of both bodies
+        let view = |coord: DVec3, radius: f64| {
+            let diff = coord - o_coord;
+            let distance = diff.length();
+            (
+                diff / distance,
+                distance,
+                (radius / distance).min(1.0).asin(),
+            )
+        };
+        let (t_dir, t_dist, t_radius) = view(t_coord, t_size);
+        let (c_dir, c_dist, c_radius) = view(c_coord, c_size);
+        if c_dist >= t_dist {
+            continue;
+        }
+        let reach = t_radius + c_radius;
+        if t_dir.dot(c_dir) < (2.0 * reach).min(PI).cos() {
+            continue;
+        }
+
+        let margin = t_dir.angle_between(c_dir) - reach;
+        near.insert(key, margin);
+        if margin >= 0.0 {
+            continue;
+        }
+        overlapping.insert(key);
+
+        let separation = margin + reach;
+        let magnitude = -margin / (2.0 * t_radius);
+        let geometry = if separation <= c_radius - t_radius {
+            Geometry::Umbra
+        } else if separation <= t_radius - c_radius {
+            Geometry::Antumbra
+        } else {
+            Geometry::Penumbra
+        };
+
+        let previous = detector.near.get(&key).copied();
+        let overlap = detector.active.entry(key).or_insert_with(|| Overlap {
+            kind: match (t_star, c_radius > 0.5 * t_radius) {
+                (true, true) => EclipseKind::Eclipse,
+                (true, false) => EclipseKind::Transit,
+                (false, _) => EclipseKind::Occultation,
+            },
+            start: match previous {
+                Some(previous) if previous > 0.0 => crossing(last_time, previous, now, margin),
+                _ => now,
+            },
+            maximum: now,
+            magnitude: 0.0,
+            geometry,
+            margin,
+            checkpoint: Checkpoint {
+                time: now,
+                bodies: Vec::new(),
+            },
+        });
+        overlap.margin = margin;
+        overlap.geometry = overlap.geometry.max(geometry);
+        if magnitude > overlap.magnitude {
+            overlap.magnitude = magnitude;
+            overlap.maximum = now;
+            overlap.checkpoint = Checkpoint::capture(now, &states);
+        }
+    }
+    detector.candidates = candidates;
+
+    let finished: Vec<_> = detector
+        .active
+        .keys()
+        .filter(|key| !overlapping.contains(*key))
+        .copied()
+        .collect();
+    for key in finished {
+        let overlap = detector.active.remove(&key).unwrap();
+        let end = match near.get(&key) {
+            Some(&margin) => crossing(last_time, overlap.margin, now, margin),
+            None => now,
+        };
+        let name = |entity| {
+            bodies
+                .iter()
+                .find(|body| body.0 == entity)
+                .map_or("?".to_string(), |body| body.1.to_string())
+        };
+        let (o, c, t) = key;
+
+        log.record(LoggedEvent {
+            title: format!("{:?}", overlap.kind),
+            detail: format!(
+                "{} over {} from {}, {:?} mag {:.3}",
+                name(c),
+                name(t),
+                name(o),
+                overlap.geometry,
+                overlap.magnitude
+            ),
+            start: Some(overlap.start),
+            maximum: overlap.maximum,
+            end: Some(end),
+            checkpoint: overlap.checkpoint,
+        });
+    }
+
+    detector.near = near;
+    detector.last_time = now;
+}
+
+/// Time at which a margin sampled at two instants crosses zero.
+fn crossing(t0: f64, m0: f64, t1: f64, m1: f64) -> f64 {
+    if m0 == m1 {
+        t1
+    } else {
+        t0 + (t1 - t0) * m0 / (m0 - m1)
+    }
+}
+
+pub fn spawn_event_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        EventPanel,
+    ));
+}
+
+pub fn update_event_panel(
+    log: Res<EventLog>,
+    observer: Res<EventObserver>,
+    names: Query<&Name>,
+    mut panel: Query<&mut Text, With<EventPanel>>,
+) {
+    let mut text = panel.single_mut();
+    if !log.visible {
+        text.sections[0].value.clear();
+        return;
+    }
+
+    let name = |observer: Option<Entity>, default: &str| match observer {
+        Some(entity) => names.get(entity).map_or("?".to_string(), |n| n.to_string()),
+        None => default.to_string(),
+    };
+    let mut lines = vec![format!(
+        "Eclipses seen from {}, conjunctions from {}",
+        name(observer.eclipses, "all bodies"),
+        name(observer.conjunctions, "Earth")
+    )];
+    let first = (log.selected + 1).saturating_sub(PANEL_ROWS);
+    for (i, event) in log.events.iter().enumerate().skip(first).take(PANEL_ROWS) {
+        let marker = if i == log.selected { ">" } else { " " };
+        lines.push(format!(
+            "{}{} {}: {}",
+            marker,
+            format_time(event.maximum),
+            event.title,
+            event.detail
+        ));
+    }
+    if log.events.is_empty() {
+        lines.push("No events yet".to_string());
+    }
+
+    text.sections[0].value = lines.join("\n");
+}
+
+/// `E` shows the log, `Up`/`Down` select an event, `J` jumps to its maximum,
+/// `O` restricts eclipse detection to the focused body or back to all bodies
+/// and `C` observes conjunctions from the focused body.
+#[allow(clippy::too_many_arguments)]
+pub fn navigate_events(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut log: ResMut<EventLog>,
+    mut observer: ResMut<EventObserver>,
+    mut clock: ResMut<SimClock>,
+    mut jumps: EventWriter<ClockJumped>,
+    mut bodies: Query<(&mut Coord, &mut Velocity, &mut Transform, &mut Trajectory)>,
+    focusable: Query<Entity, With<Mass>>,
+    camera: Query<&Ordinal>,
+) {
+    if keyboard_input.just_pressed(KeyCode::E) {
+        log.visible = !log.visible;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::O) {
+        observer.eclipses = match observer.eclipses {
+            Some(_) => None,
+            None => focusable.iter().nth(camera.single().0),
+        };
+    }
+
+    if keyboard_input.just_pressed(KeyCode::C) {
+        observer.conjunctions = focusable.iter().nth(camera.single().0);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        log.selected = log.selected.saturating_sub(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        log.selected = (log.selected + 1).min(log.events.len().saturating_sub(1));
+    }
+
+    if keyboard_input.just_pressed(KeyCode::J) {
+        let Some(event) = log.events.get(log.selected) else {
+            return;
+        };
+        let checkpoint = &event.checkpoint;
+        for &(entity, coord, velocity, trail) in &checkpoint.bodies {
+            if let Ok((mut c, mut v, mut transform, mut trajectory)) = bodies.get_mut(entity) {
+                c.0 = coord;
+                v.0 = velocity;
+                transform.translation = (coord * SCALE).as_vec3();
+                trajectory.0.truncate(trail);
+            }
+        }
+        clock.0 = checkpoint.time;
+        jumps.send(ClockJumped);
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+};
+
+use bevy::{math::DVec3, prelude::*};
+use serde::Serialize;
+
+use crate::{argument, constants::*, replay::Recording, Coord, Mass, Ordinal, SimClock, Velocity};
+
+/// Files are written as `<prefix>.csv`, `<prefix>.jsonl` and `<prefix>.col`.
+const EXPORT_PREFIX: &str = "trajectories";
+const MAGIC: &[u8; 4] = b"SCOL";
+/// Layout of the columnar files.
+pub const COLUMNS_VERSION: u32 = 1;
+
+/// Units positions and velocities are exported in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Units {
+    /// m and m/s
+    Meters,
+    /// km and km/s
+    Kilometers,
+    /// AU and AU/day
+    AstronomicalUnits,
+}
+
+impl Units {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "m" => Some(Units::Meters),
+            "km" => Some(Units::Kilometers),
+            "au" => Some(Units::AstronomicalUnits),
+            _ => None,
+        }
+    }
+
+    /// Suffixes of the position and velocity columns.
+    fn suffixes(self) -> (&'static str, &'static str) {
+        match self {
+            Units::Meters => ("m", "m_s"),
+            Units::Kilometers => ("km", "km_s"),
+            Units::AstronomicalUnits => ("au", "au_d"),
+        }
+    }
+
+    /// Meters, and meters per second, in one unit of position and velocity.
+    fn scales(self) -> (f64, f64) {
+        match self {
+            Units::Meters => (1.0, 1.0),
+            Units::Kilometers => (1e3, 1e3),
+            Units::AstronomicalUnits => (AU, AU / 86400.0),
+        }
+    }
+}
+
+/// What is exported and how; `S` starts and stops exporting the running
+/// simulation and `Q` makes the focused body the origin of the frame, or
+/// goes back to the simulation's own.
+#[derive(Resource)]
+pub struct ExportSettings {
+    /// Simulated seconds between samples
+    pub cadence: f64,
+    pub units: Units,
+    /// Body the positions and velocities are relative to, the barycentric
+    /// frame the simulation runs in when `None`
+    pub origin: Option<String>,
+    export: Option<Export>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        ExportSettings {
+            cadence: EXPORT_CADENCE,
+            units: Units::Kilometers,
+            origin: None,
+            export: None,
+        }
+    }
+}
+
+impl ExportSettings {
+    /// Settings given on the command line with `--export-cadence <seconds>`,
+    /// `--export-units <m|km|au>` and `--export-origin <body>`.
+    pub fn from_args() -> Self {
+        let defaults = ExportSettings::default();
+        ExportSettings {
+            cadence: argument("--export-cadence")
+                .and_then(|cadence| cadence.parse().ok())
+                .filter(|&cadence: &f64| cadence > 0.0)
+                .unwrap_or(defaults.cadence),
+            units: argument("--export-units")
+                .and_then(|units| Units::parse(&units))
+                .unwrap_or(defaults.units),
+            origin: argument("--export-origin"),
+            export: None,
+        }
+    }
+}
+
+/// Body states sampled at a cadence, relative to the origin and in the units
+/// of the settings they were taken with.
+pub struct Export {
+    units: Units,
+    origin: Option<String>,
+    names: Vec<String>,
+    times: Vec<f64>,
+    bodies: Vec<u32>,
+    positions: Vec<DVec3>,
+    velocities: Vec<DVec3>,
+}
+
+#[derive(Component)]
+pub struct ExportPanel;
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    time: f64,
+    body: &'a str,
+    position: [f64; 3],
+    velocity: [f64; 3],
+}
+
+impl Export {
+    fn new(settings: &ExportSettings) -> Self {
+        Export {
+            units: settings.units,
+            origin: settings.origin.clone(),
+            names: Vec::new(),
+            times: Vec::new(),
+            bodies: Vec::new(),
+            positions: Vec::new(),
+            velocities: Vec::new(),
+        }
+    }
+
+    fn last_time(&self) -> Option<f64> {
+        self.times.last().copied()
+    }
+
+    /// Drops the rows sampled after `time`, left behind when the clock jumps
+    /// back.
+    fn truncate(&mut self, time: f64) {
+        let kept = self.times.partition_point(|&sampled| sampled <= time);
+        self.times.truncate(kept);
+        self.bodies.truncate(kept);
+        self.positions.truncate(kept);
+        self.velocities.truncate(kept);
+    }
+
+    /// Adds a row for each body, relative to the origin if it is among them;
+    /// nothing is sampled while the origin is missing.
+    fn sample<'a>(&mut self, time: f64, states: impl Iterator<Item = (&'a str, DVec3, DVec3)>) {
+        let states: Vec<_> = states.collect();
+        let origin = match &self.origin {
+            Some(origin) => match states.iter().find(|(name, ..)| name == origin) {
+                Some(&(_, position, velocity)) => (position, velocity),
+                None => return,
+            },
+            None => (DVec3::ZERO, DVec3::ZERO),
+        };
+        let (length, speed) = self.units.scales();
+        for (name, position, velocity) in states {
+            let body = match self.names.iter().position(|n| n == name) {
+                Some(index) => index,
+                None => {
+                    self.names.push(name.to_string());
+                    self.names.len() - 1
+                }
+            };
+            self.times.push(time);
+            self.bodies.push(body as u32);
+            self.positions.push((position - origin.0) / length);
+            self.velocities.push((velocity - origin.1) / speed);
+        }
+    }
+
+    fn rows(&self) -> impl Iterator<Item = JsonRow<'_>> {
+        (0..self.times.len()).map(|row| JsonRow {
+            time: self.times[row],
+            body: &self.names[self.bodies[row] as usize],
+            position: self.positions[row].to_array(),
+            velocity: self.velocities[row].to_array(),
+        })
+    }
+
+    fn csv(&self) -> String {
+        let (position, velocity) = self.units.suffixes();
+        let mut csv = format!(
+            "time_s,body,x_{p},y_{p},z_{p},vx_{v},vy_{v},vz_{v}\n",
+            p = position,
+            v = velocity
+        );
+        for row in self.rows() {
+            let [x, y, z] = row.position;
+            let [vx, vy, vz] = row.velocity;
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{}",
+                row.time, row.body, x, y, z, vx, vy, vz
+            );
+        }
+        csv
+    }
+
+    fn json_lines(&self) -> serde_json::Result<String> {
+        let mut lines = String::new();
+        for row in self.rows() {
+            lines.push_str(&serde_json::to_string(&row)?);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+
+    /// Writes the columns one after the other, little-endian: the magic
+    /// `SCOL`, the version, row count and units (0 m, 1 km, 2 AU) as u32, the
+    /// origin (empty for the simulation's frame) and the count and names of
+    /// the bodies as u32 length-prefixed UTF-8, then the time (f64), body
+    /// index (u32) and x, y, z, vx, vy, vz (f64) columns.
+    fn write_columns(&self, path: &str) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let string = |out: &mut BufWriter<File>, value: &str| {
+            out.write_all(&(value.len() as u32).to_le_bytes())?;
+            out.write_all(value.as_bytes())
+        };
+        out.write_all(MAGIC)?;
+        out.write_all(&COLUMNS_VERSION.to_le_bytes())?;
+        out.write_all(&(self.times.len() as u32).to_le_bytes())?;
+        out.write_all(&(self.units as u32).to_le_bytes())?;
+        string(&mut out, self.origin.as_deref().unwrap_or(""))?;
+        out.write_all(&(self.names.len() as u32).to_le_bytes())?;
+        for name in &self.names {
+            string(&mut out, name)?;
+        }
+        for time in &self.times {
+            out.write_all(&time.to_le_bytes())?;
+        }
+        for body in &self.bodies {
+            out.write_all(&body.to_le_bytes())?;
+        }
+        for column in [&self.positions, &self.velocities] {
+            for axis in 0..3 {
+                for value in column.iter() {
+                    out.write_all(&value[axis].to_le_bytes())?;
+                }
+            }
+        }
+        out.flush()
+    }
+
+    /// Writes the CSV, JSON Lines and columnar files, returning the paths.
+    pub fn write(&self, prefix: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let paths: Vec<_> = ["csv", "jsonl", "col"]
+            .iter()
+            .map(|extension| format!("{}.{}", prefix, extension))
+            .collect();
+        fs::write(&paths[0], self.csv())?;
+        fs::write(&paths[1], self.json_lines()?)?;
+        self.write_columns(&paths[2])?;
+        Ok(paths)
+    }
+
+    /// Samples a recorded run at the cadence of the settings.
+    pub fn from_recording(recording: &Recording, settings: &ExportSettings) -> Self {
+        let mut export = Export::new(settings);
+        let samples = ((recording.end() - recording.start()) / settings.cadence).floor() as usize;
+        for sample in 0..=samples {
+            let time = recording.start() + sample as f64 * settings.cadence;
+            export.sample(time, recording.states(time));
+        }
+        export
+    }
+}
+
+/// Exports the recording at `path` to files named after it, for `--export`
+/// on the command line, without starting the simulation.
+pub fn export_recording(path: &str, settings: &ExportSettings) {
+    let recording = match Recording::read(path) {
+        Ok(recording) => recording,
+        Err(err) => {
+            warn!("Could not load {}: {}", path, err);
+            return;
+        }
+    };
+    if let Some(origin) = &settings.origin {
+        if !recording.names().contains(origin) {
+            warn!(
+                "{} is not in {}, expected one of: {}",
+                origin,
+                path,
+                recording.names().join(", ")
+            );
+            return;
+        }
+    }
+    let prefix = path.trim_end_matches(".gz").trim_end_matches(".bin");
+    match Export::from_recording(&recording, settings).write(prefix) {
+        Ok(paths) => info!("Exported {}", paths.join(", ")),
+        Err(err) => warn!("Could not export {}: {}", path, err),
+    }
+}
+
+pub fn control_export(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<ExportSettings>,
+    focusable: Query<&Name, With<Mass>>,
+    camera: Query<&Ordinal>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Q) {
+        settings.origin = match settings.origin {
+            Some(_) => None,
+            None => focusable
+                .iter()
+                .nth(camera.single().0)
+                .map(|name| name.to_string()),
+        };
+    }
+    if keyboard_input.just_pressed(KeyCode::S) {
+        match settings.export.take() {
+            Some(export) => match export.write(EXPORT_PREFIX) {
+                Ok(paths) => info!("Exported {}", paths.join(", ")),
+                Err(err) => warn!("Could not export {}: {}", EXPORT_PREFIX, err),
+            },
+            None => match &settings.origin {
+                Some(origin) if !focusable.iter().any(|name| name.as_str() == origin) => {
+                    warn!(
+                        "Cannot export relative to {}, it is not in the simulation",
+                        origin
+                    )
+                }
+                _ => settings.export = Some(Export::new(&settings)),
+            },
+        }
+    }
+}
+
+/// Samples every body once `cadence` has passed since the last sample.
+pub fn sample_export(
+    clock: Res<SimClock>,
+    mut settings: ResMut<ExportSettings>,
+    bodies: Query<(&Name, &Coord, &Velocity)>,
+) {
+    let cadence = settings.cadence;
+    let Some(export) = &mut settings.export else {
+        return;
+    };
+    export.truncate(clock.0);
+    if export
+        .last_time()
+        .is_some_and(|last| clock.0 - last < cadence)
+    {
+        return;
+    }
+    export.sample(
+        clock.0,
+        bodies
+            .iter()
+            .map(|(name, coord, velocity)| (name.as_str(), coord.0, velocity.0)),
+    );
+}
+
+pub fn spawn_export_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(50.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+        ExportPanel,
+    ));
+}
+
+pub fn update_export_panel(
+    settings: Res<ExportSettings>,
+    mut panel: Query<&mut Text, With<ExportPanel>>,
+) {
+    let text = &mut panel.single_mut().sections[0].value;
+    let Some(export) = &settings.export else {
+        text.clear();
+        return;
+    };
+    *text = format!(
+        "Exporting every {:.0} s in {:?} relative to {}, {} rows",
+        settings.cadence,
+        export.units,
+        export.origin.as_deref().unwrap_or("the barycenter"),
+        export.times.len()
+    );
+}
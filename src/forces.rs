@@ -0,0 +1,350 @@
+use bevy::{math::DVec3, prelude::*, utils::HashMap};
+
+use crate::{
+    attraction, constants::*, lighting::Luminosity, snapshot::restore_snapshot, update_position,
+    Coord, Mass, Ordinal, Physics, Star, Velocity,
+};
+
+/// Marsden, Sekanina and Yeomans (1973) water sublimation law.
+const SUBLIMATION_ALPHA: f64 = 0.1113;
+const SUBLIMATION_R0: f64 = 2.808 * AU;
+const SUBLIMATION_M: f64 = 2.15;
+const SUBLIMATION_N: f64 = 5.093;
+const SUBLIMATION_K: f64 = 4.6142;
+
+/// Acceleration of 1 AU/day², the unit non-gravitational parameters are
+/// usually published in.
+const AU_PER_DAY2: f64 = AU / (86400.0 * 86400.0);
+
+/// Keys that, pressed while holding `F`, toggle the forces on the focused
+/// body, in order of registration.
+const KEYS: [KeyCode; 9] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// A force other than gravity, computed for each star from the body's
+/// position and velocity relative to it. Stars other than the Sun are seen
+/// as the Sun would be from the distance receiving the same light.
+pub trait Force: Default + Send + Sync + 'static {
+    const NAME: &'static str;
+
+    fn acceleration(&self, r: DVec3, v: DVec3) -> DVec3;
+}
+
+/// Parameters of a force acting on a body, and whether it currently does.
+#[derive(Component)]
+pub struct NonGravitational<F: Force> {
+    pub enabled: bool,
+    pub model: F,
+}
+
+impl<F: Force> NonGravitational<F> {
+    pub fn new(model: F) -> Self {
+        NonGravitational {
+            enabled: true,
+            model,
+        }
+    }
+}
+
+/// Names of the registered forces, in order of registration.
+#[derive(Resource, Default)]
+pub struct ForceRegistry(Vec<&'static str>);
+
+/// Latest acceleration of each force on each body it is attached to, and
+/// whether it is enabled; `D` shows those of the focused body.
+#[derive(Resource, Default)]
+pub struct ForceDiagnostics {
+    pub visible: bool,
+    accelerations: HashMap<(Entity, &'static str), (bool, DVec3)>,
+}
+
+#[derive(Component)]
+pub struct ForcePanel;
+
+/// Whether each force acting on a body is enabled, by body and force name,
+/// as snapshots save them; the forces in `pending` are put back on their
+/// bodies, and the others taken off, on the update a snapshot is restored.
+#[derive(Resource, Default)]
+pub struct ForceStates {
+    pub attached: HashMap<(Entity, &'static str), bool>,
+    pub pending: HashMap<Entity, Vec<(String, bool)>>,
+}
+
+pub trait RegisterForce {
+    /// Applies `F` in the `Physics` schedule, after gravity and before the
+    /// positions are updated, to the bodies that carry it.
+    fn register_force<F: Force>(&mut self) -> &mut Self;
+}
+
+impl RegisterForce for App {
+    fn register_force<F: Force>(&mut self) -> &mut Self {
+        self.init_resource::<ForceRegistry>()
+            .init_resource::<ForceStates>()
+            .world
+            .resource_mut::<ForceRegistry>()
+            .0
+            .push(F::NAME);
+        self.add_systems(
+            Physics,
+            apply_force::<F>.after(attraction).before(update_position),
+        )
+        .add_systems(
+            Update,
+            (toggle_force::<F>, sync_force::<F>.after(restore_snapshot)),
+        )
+    }
+}
+
+/// Radiation of the star pushing on a surface facing it, part of which is
+/// reflected.
+pub struct RadiationPressure {
+    /// Cross section over mass, m²/kg
+    pub area_to_mass: f64,
+    /// Fraction of the light reflected rather than absorbed
+    pub reflectivity: f64,
+}
+
+impl Default for RadiationPressure {
+    fn default() -> Self {
+        RadiationPressure {
+            area_to_mass: 0.01,
+            reflectivity: 0.3,
+        }
+    }
+}
+
+impl Force for RadiationPressure {
+    const NAME: &'static str = "Radiation pressure";
+
+    fn acceleration(&self, r: DVec3, _: DVec3) -> DVec3 {
+        let distance = r.length();
+        r / distance
+            * (SOLAR_RADIATION_PRESSURE
+                * (AU / distance).powi(2)
+                * (1.0 + self.reflectivity)
+                * self.area_to_mass)
+    }
+}
+
+/// How actively ice sublimates at a distance from the star, relative to 1 AU.
+pub fn sublimation(distance: f64) -> f64 {
+    let ratio = distance / SUBLIMATION_R0;
+    SUBLIMATION_ALPHA
+        * ratio.powf(-SUBLIMATION_M)
+        * (1.0 + ratio.powf(SUBLIMATION_N)).powf(-SUBLIMATION_K)
+}
+
+/// Reaction to the gas a comet's nucleus sheds as the star warms it, in
+/// the radial, transverse and normal directions.
+pub struct Outgassing {
+    /// Marsden's A1, A2 and A3 parameters, m/s² at 1 AU
+    pub parameters: [f64; 3],
+}
+
+impl Default for Outgassing {
+    fn default() -> Self {
+        let [a1, a2, a3] = HALLEY_OUTGASSING;
+        Outgassing::from_au_per_day2(a1, a2, a3)
+    }
+}
+
+impl Outgassing {
+    /// Marsden's parameters given in AU/day², as they are published.
+    pub fn from_au_per_day2(a1: f64, a2: f64, a3: f64) -> Self {
+        Outgassing {
+            parameters: [a1 * AU_PER_DAY2, a2 * AU_PER_DAY2, a3 * AU_PER_DAY2],
+        }
+    }
+}
+
+impl Force for Outgassing {
+    const NAME: &'static str = "Outgassing";
+
+    fn acceleration(&self, r: DVec3, v: DVec3) -> DVec3 {
+        let g = sublimation(r.length());
+        let radial = r.normalize();
+        let normal = r.cross(v).normalize();
+        let transverse = normal.cross(radial);
+        let [a1, a2, a3] = self.parameters;
+        (radial * a1 + transverse * a2 + normal * a3) * g
+    }
+}
+
+/// Thermal recoil of a small rotating body reradiating sunlight, which makes
+/// its orbit drift; modelled as a transverse acceleration falling off with
+/// the square of the distance.
+pub struct Yarkovsky {
+    /// Transverse acceleration at 1 AU, m/s²
+    pub a2: f64,
+}
+
+impl Default for Yarkovsky {
+    fn default() -> Self {
+        // 101955 Bennu
+        Yarkovsky {
+            a2: -4.618e-14 * AU_PER_DAY2,
+        }
+    }
+}
+
+impl Force for Yarkovsky {
+    const NAME: &'static str = "Yarkovsky";
+
+    fn acceleration(&self, r: DVec3, v: DVec3) -> DVec3 {
+        let distance = r.length();
+        let transverse = r.cross(v).cross(r).normalize();
+        transverse * self.a2 * (AU / distance).powi(2)
+    }
+}
+
+pub fn apply_force<F: Force>(
+    mut diagnostics: ResMut<ForceDiagnostics>,
+    stars: Query<(&Coord, &Velocity, &Luminosity), With<Star>>,
+    mut bodies: Query<(Entity, &Coord, &mut Velocity, &NonGravitational<F>), Without<Star>>,
+) {
+    for (entity, coord, mut velocity, force) in &mut bodies {
+        let acceleration = if force.enabled {
+            stars
+                .iter()
+                .map(|(star_coord, star_velocity, luminosity)| {
+                    force.model.acceleration(
+                        (coord.0 - star_coord.0) / luminosity.0.sqrt(),
+                        velocity.0 - star_velocity.0,
+                    )
+                })
+                .sum()
+        } else {
+            DVec3::ZERO
+        };
+        velocity.0 += acceleration * DT as f64;
+        diagnostics
+            .accelerations
+            .insert((entity, F::NAME), (force.enabled, acceleration));
+    }
+}
+
+/// Applies the restored state of `F` to the bodies of a snapshot, and
+/// records which bodies it acts on.
+pub fn sync_force<F: Force>(
+    mut commands: Commands,
+    mut states: ResMut<ForceStates>,
+    mut bodies: Query<(Entity, &mut NonGravitational<F>)>,
+) {
+    for (&entity, forces) in &states.pending {
+        let enabled = forces
+            .iter()
+            .find(|(name, _)| name == F::NAME)
+            .map(|force| force.1);
+        match (enabled, bodies.get_mut(entity)) {
+            (Some(enabled), Ok((_, mut force))) => force.enabled = enabled,
+            (Some(enabled), Err(_)) => {
+                commands.entity(entity).insert(NonGravitational {
+                    enabled,
+                    model: F::default(),
+                });
+            }
+            (None, _) => {
+                commands.entity(entity).remove::<NonGravitational<F>>();
+            }
+        }
+    }
+
+    states.attached.retain(|key, _| key.1 != F::NAME);
+    for (entity, force) in &bodies {
+        states.attached.insert((entity, F::NAME), force.enabled);
+    }
+}
+
+/// Holding `F` and pressing the force's key switches it on or off for the
+/// focused body, attaching it with its default parameters the first time.
+pub fn toggle_force<F: Force>(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    registry: Res<ForceRegistry>,
+    mut bodies: Query<(Entity, Option<&mut NonGravitational<F>>), With<Mass>>,
+    camera: Query<&Ordinal>,
+) {
+    let key = registry
+        .0
+        .iter()
+        .position(|&name| name == F::NAME)
+        .and_then(|index| KEYS.get(index));
+    let Some(&key) = key else {
+        return;
+    };
+    if !keyboard_input.pressed(KeyCode::F) || !keyboard_input.just_pressed(key) {
+        return;
+    }
+    let Some((entity, force)) = bodies.iter_mut().nth(camera.single().0) else {
+        return;
+    };
+    match force {
+        Some(mut force) => force.enabled = !force.enabled,
+        None => {
+            commands
+                .entity(entity)
+                .insert(NonGravitational::new(F::default()));
+        }
+    }
+}
+
+pub fn spawn_force_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(25.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        ForcePanel,
+    ));
+}
+
+pub fn update_force_panel(
+    keyboard_input: Res<Input<KeyCode>>,
+    registry: Res<ForceRegistry>,
+    mut diagnostics: ResMut<ForceDiagnostics>,
+    bodies: Query<(Entity, &Name), With<Mass>>,
+    camera: Query<&Ordinal>,
+    mut panel: Query<&mut Text, With<ForcePanel>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::D) {
+        diagnostics.visible = !diagnostics.visible;
+    }
+    let text = &mut panel.single_mut().sections[0].value;
+    if !diagnostics.visible {
+        text.clear();
+        return;
+    }
+    let Some((entity, name)) = bodies.iter().nth(camera.single().0) else {
+        return;
+    };
+
+    let mut lines = vec![format!("Non-gravitational forces on {}", name)];
+    for (index, force) in registry.0.iter().enumerate() {
+        let state = match diagnostics.accelerations.get(&(entity, *force)) {
+            Some((true, acceleration)) => format!("{:.3e} m/s2", acceleration.length()),
+            Some((false, _)) => "off".to_string(),
+            None => "-".to_string(),
+        };
+        lines.push(format!("F+{} {}: {}", index + 1, force, state));
+    }
+    *text = lines.join("\n");
+}
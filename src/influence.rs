@@ -0,0 +1,78 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{constants::*, Coord, Kind, Mass, Primary};
+
+/// Kinds of bodies whose Hill sphere and sphere of influence are drawn.
+/// Hold `H` and press `1` to `4` to toggle planets, dwarf planets, satellites
+/// and test particles.
+#[derive(Resource, Default)]
+pub struct InfluenceOverlay(pub HashSet<Kind>);
+
+/// Radius within which the body holds satellites against the tides of its
+/// primary, at its current distance.
+pub fn hill_radius(mass: f64, primary_mass: f64, distance: f64) -> f64 {
+    distance * (mass / (3.0 * primary_mass)).cbrt()
+}
+
+/// Laplace radius within which the body's attraction, rather than its
+/// primary's, is the better center for a patched conic.
+pub fn sphere_of_influence(mass: f64, primary_mass: f64, distance: f64) -> f64 {
+    distance * (mass / primary_mass).powf(0.4)
+}
+
+pub fn toggle_influence_overlay(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut overlay: ResMut<InfluenceOverlay>,
+) {
+    if !keyboard_input.pressed(KeyCode::H) {
+        return;
+    }
+    let keys = [
+        (KeyCode::Key1, Kind::Planet),
+        (KeyCode::Key2, Kind::DwarfPlanet),
+        (KeyCode::Key3, Kind::Satellite),
+        (KeyCode::Key4, Kind::Particle),
+    ];
+    for (key, kind) in keys {
+        if keyboard_input.just_pressed(key) && !overlay.0.remove(&kind) {
+            overlay.0.insert(kind);
+        }
+    }
+}
+
+pub fn draw_influence_spheres(
+    mut gizmos: Gizmos,
+    overlay: Res<InfluenceOverlay>,
+    bodies: Query<(&Coord, &Mass, &Kind, &Primary)>,
+    primaries: Query<(&Coord, &Mass)>,
+    camera: Query<&Transform, With<Camera>>,
+) {
+    if overlay.0.is_empty() {
+        return;
+    }
+    let normal = camera.single().rotation * Vec3::Z;
+
+    for (coord, mass, kind, primary) in &bodies {
+        if !overlay.0.contains(kind) {
+            continue;
+        }
+        let Ok((primary_coord, primary_mass)) = primaries.get(primary.0) else {
+            continue;
+        };
+        let distance = coord.0.distance(primary_coord.0);
+        let center = (coord.0 * SCALE).as_vec3();
+
+        gizmos.circle(
+            center,
+            normal,
+            (hill_radius(mass.0, primary_mass.0, distance) * SCALE) as f32,
+            Color::TEAL,
+        );
+        gizmos.circle(
+            center,
+            normal,
+            (sphere_of_influence(mass.0, primary_mass.0, distance) * SCALE) as f32,
+            Color::ORANGE,
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use std::f64::consts::TAU;
+
+use bevy::math::DVec3;
+
+use crate::lambert::stumpff;
+
+/// Below this reciprocal semi-major axis, in 1/m, an orbit is treated as
+/// parabolic and time spans are not reduced to a single period.
+const PARABOLIC: f64 = 1e-18;
+
+/// Solves the universal Kepler equation for the universal anomaly reached
+/// `dt` seconds after distance `r0`, radial velocity `vr0`, on an orbit of
+/// reciprocal semi-major axis `alpha` (negative when hyperbolic).
+///
+/// The time equation grows with the anomaly at a rate proportional to the
+/// distance, so Newton's method is kept inside a bracket and falls back to
+/// bisection whenever a step would leave it.
+pub fn universal_anomaly(r0: f64, vr0: f64, alpha: f64, mu: f64, dt: f64) -> f64 {
+    let sqrt_mu = mu.sqrt();
+    let time = |chi: f64| {
+        let z = alpha * chi * chi;
+        let (c, s) = stumpff(z);
+        let t =
+            (r0 * vr0 / sqrt_mu * chi * chi * c + (1.0 - alpha * r0) * chi.powi(3) * s + r0 * chi)
+                / sqrt_mu;
+        let rate =
+            (r0 * vr0 / sqrt_mu * chi * (1.0 - z * s) + (1.0 - alpha * r0) * chi * chi * c + r0)
+                / sqrt_mu;
+        (t - dt, rate)
+    };
+
+    // Time grows exponentially with the anomaly on a hyperbola, where Newton's
+    // method from far above the root would only gain one e-folding per step
+    let hyperbolic = if alpha < -PARABOLIC {
+        let a = 1.0 / alpha;
+        let argument = -2.0 * mu * alpha * dt
+            / (r0 * vr0 + dt.signum() * (-mu * a).sqrt() * (1.0 - r0 * alpha));
+        dt.signum() * (-a).sqrt() * argument.ln()
+    } else {
+        f64::NAN
+    };
+    let guess = if alpha > PARABOLIC {
+        sqrt_mu * alpha * dt
+    } else if hyperbolic.is_finite() && hyperbolic != 0.0 {
+        hyperbolic
+    } else {
+        sqrt_mu * dt / r0
+    };
+    let (mut low, mut high) = if dt >= 0.0 {
+        (0.0, guess.max(f64::MIN_POSITIVE))
+    } else {
+        (guess.min(-f64::MIN_POSITIVE), 0.0)
+    };
+    while time(high).0 < 0.0 {
+        high *= 2.0;
+    }
+    while time(low).0 > 0.0 {
+        low *= 2.0;
+    }
+
+    let mut chi = guess.clamp(low, high);
+    for _ in 0..100 {
+        let (error, rate) = time(chi);
+        if error.abs() < 1e-9 {
+            break;
+        }
+        if error < 0.0 {
+            low = chi;
+        } else {
+            high = chi;
+        }
+        let newton = chi - error / rate;
+        chi = if newton > low && newton < high {
+            newton
+        } else {
+            (low + high) / 2.0
+        };
+        if (high - low).abs() <= 1e-15 * chi.abs().max(1.0) {
+            break;
+        }
+    }
+    chi
+}
+
+/// Position and velocity `dt` seconds later on the two-body orbit through
+/// `r` and `v` around a body of gravitational parameter `mu`; elliptic,
+/// parabolic and hyperbolic orbits alike, forwards or backwards in time.
+pub fn propagate(r: DVec3, v: DVec3, mu: f64, dt: f64) -> (DVec3, DVec3) {
+    let r0 = r.length();
+    let vr0 = r.dot(v) / r0;
+    let alpha = 2.0 / r0 - v.length_squared() / mu;
+
+    // Whole revolutions change nothing and would only cost precision
+    let dt = if alpha > PARABOLIC {
+        dt % (TAU / (mu * alpha.powi(3)).sqrt())
+    } else {
+        dt
+    };
+    let chi = universal_anomaly(r0, vr0, alpha, mu, dt);
+    let z = alpha * chi * chi;
+    let (c, s) = stumpff(z);
+
+    let f = 1.0 - chi * chi / r0 * c;
+    let g = dt - chi.powi(3) / mu.sqrt() * s;
+    let r1 = r * f + v * g;
+    let r1n = r1.length();
+    let f_dot = mu.sqrt() / (r1n * r0) * chi * (z * s - 1.0);
+    let g_dot = 1.0 - chi * chi / r1n * c;
+    (r1, r * f_dot + v * g_dot)
+}
+
+/// Orbital period, or infinity for an orbit that does not close.
+pub fn period(r: DVec3, v: DVec3, mu: f64) -> f64 {
+    let alpha = 2.0 / r.length() - v.length_squared() / mu;
+    if alpha > PARABOLIC {
+        TAU / (mu * alpha.powi(3)).sqrt()
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Shape and orientation of the conic through a state vector.
+pub struct Elements {
+    /// Semi-latus rectum, finite for every kind of conic
+    pub semi_latus_rectum: f64,
+    pub eccentricity: f64,
+    /// Unit vector from the focus towards periapsis; along the position when
+    /// the orbit is circular
+    pub periapsis: DVec3,
+    /// Unit vector along the angular momentum
+    pub normal: DVec3,
+    /// Unit vector towards the ascending node on the ecliptic, if the orbit
+    /// is inclined to it
+    pub ascending_node: Option<DVec3>,
+}
+
+impl Elements {
+    pub fn from_state(r: DVec3, v: DVec3, mu: f64) -> Self {
+        let h = r.cross(v);
+        let e = v.cross(h) / mu - r.normalize();
+        let eccentricity = e.length();
+        let node = DVec3::Z.cross(h);
+        Elements {
+            semi_latus_rectum: h.length_squared() / mu,
+            eccentricity,
+            periapsis: if eccentricity > 1e-9 {
+                e / eccentricity
+            } else {
+                r.normalize()
+            },
+            normal: h.normalize(),
+            ascending_node: (node.length() > 1e-9 * h.length()).then(|| node.normalize()),
+        }
+    }
+
+    /// Semi-major axis, negative for a hyperbola.
+    pub fn semi_major_axis(&self) -> f64 {
+        self.semi_latus_rectum / (1.0 - self.eccentricity.powi(2))
+    }
+
+    pub fn periapsis_distance(&self) -> f64 {
+        self.semi_latus_rectum / (1.0 + self.eccentricity)
+    }
+
+    /// Farthest distance, if the orbit is closed.
+    pub fn apoapsis_distance(&self) -> Option<f64> {
+        (self.eccentricity < 1.0).then(|| self.semi_latus_rectum / (1.0 - self.eccentricity))
+    }
+
+    /// True anomaly of a direction in the orbit plane.
+    pub fn true_anomaly(&self, direction: DVec3) -> f64 {
+        self.normal
+            .dot(self.periapsis.cross(direction))
+            .atan2(self.periapsis.dot(direction))
+    }
+
+    /// Position relative to the focus at a true anomaly, if the conic reaches
+    /// it.
+    pub fn position(&self, true_anomaly: f64) -> Option<DVec3> {
+        let denominator = 1.0 + self.eccentricity * true_anomaly.cos();
+        if denominator <= 0.0 {
+            return None;
+        }
+        let direction = self.periapsis * true_anomaly.cos()
+            + self.normal.cross(self.periapsis) * true_anomaly.sin();
+        Some(direction * self.semi_latus_rectum / denominator)
+    }
+
+    /// Points along the conic relative to the focus: the whole ellipse, or the
+    /// part of a hyperbola or parabola within `extent` of the focus. They are
+    /// spaced evenly in eccentric, hyperbolic or parabolic anomaly, so they
+    /// crowd where the curve bends rather than all at periapsis.
+    pub fn points(&self, segments: usize, extent: f64) -> Vec<DVec3> {
+        let (p, e) = (self.semi_latus_rectum, self.eccentricity);
+        let (x, y) = (self.periapsis, self.normal.cross(self.periapsis));
+        if e < 1.0 {
+            let a = self.semi_major_axis();
+            let b = a * (1.0 - e * e).sqrt();
+            return (0..=segments)
+                .map(|i| {
+                    let anomaly = TAU * i as f64 / segments as f64;
+                    x * a * (anomaly.cos() - e) + y * b * anomaly.sin()
+                })
+                .collect();
+        }
+
+        let extent = extent.max(2.0 * self.periapsis_distance());
+        let samples = |limit: f64, point: &dyn Fn(f64) -> DVec3| {
+            (0..=segments)
+                .map(|i| point(limit * (2.0 * i as f64 / segments as f64 - 1.0)))
+                .collect()
+        };
+        if e > 1.0 + 1e-9 {
+            let a = -self.semi_major_axis();
+            let b = a * (e * e - 1.0).sqrt();
+            let limit = ((extent / a + 1.0) / e).acosh();
+            samples(limit, &|anomaly| {
+                x * a * (e - anomaly.cosh()) + y * b * anomaly.sinh()
+            })
+        } else {
+            // Distance grows with the square of the parabolic anomaly
+            let limit = (2.0 * extent / p - 1.0).max(0.0).sqrt();
+            samples(limit, &|anomaly| {
+                x * p / 2.0 * (1.0 - anomaly * anomaly) + y * p * anomaly
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::*;
+
+    fn energy(r: DVec3, v: DVec3, mu: f64) -> f64 {
+        v.length_squared() / 2.0 - mu / r.length()
+    }
+
+    fn assert_conserved(r: DVec3, v: DVec3, mu: f64, span: f64) {
+        let (e0, h0) = (energy(r, v, mu), r.cross(v));
+        for i in 1..=64 {
+            let (r1, v1) = propagate(r, v, mu, span * i as f64 / 64.0);
+            let e1 = energy(r1, v1, mu);
+            assert!((e1 - e0).abs() < 1e-9 * e0.abs(), "energy {} vs {}", e1, e0);
+            let h1 = r1.cross(v1);
+            assert!(h1.distance(h0) < 1e-9 * h0.length(), "{} vs {}", h1, h0);
+        }
+    }
+
+    #[test]
+    fn elliptic_orbit_conserves_energy_and_closes() {
+        let mu = G * SUN_MASS;
+        let (r, v) = (DVec3::new(AU, 0.0, 0.0), DVec3::new(0.0, 35e3, 3e3));
+        let period = period(r, v, mu);
+        assert_conserved(r, v, mu, period);
+        let (r1, v1) = propagate(r, v, mu, period);
+        assert!(r1.distance(r) < 1e-6 * AU);
+        assert!(v1.distance(v) < 1e-6 * v.length());
+    }
+
+    #[test]
+    fn hyperbolic_orbit_conserves_energy() {
+        let mu = G * EARTH_MASS;
+        let (r, v) = (DVec3::new(7e6, 0.0, 0.0), DVec3::new(0.0, 12e3, 0.0));
+        assert!(energy(r, v, mu) > 0.0);
+        assert_conserved(r, v, mu, 30.0 * 86400.0);
+        assert_conserved(r, v, mu, -30.0 * 86400.0);
+    }
+}
@@ -0,0 +1,332 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    constants::*, label, unique_name, BodyBundle, CircleSize, Coord, Kind, Label, Mass, Primary,
+    Velocity,
+};
+
+/// Pairs of bodies, by name, whose Lagrange points are shown, with the camera
+/// distance in AU under which their labels appear.
+pub const LAGRANGE_PAIRS: &[(&str, &str, f64)] = &[
+    ("Sun", "Earth", 1.0),
+    ("Earth", "Moon", 0.05),
+    ("Sun", "Jupiter", 20.0),
+];
+
+#[derive(Component)]
+pub struct LagrangePoint {
+    primary: Entity,
+    secondary: Entity,
+    /// 1 to 5
+    index: usize,
+}
+
+/// `G` toggles the markers, `N` selects the next point and `T` places a test
+/// particle at the selected one.
+#[derive(Resource, Default)]
+pub struct LagrangeSettings {
+    pub visible: bool,
+    pub selected: usize,
+}
+
+pub fn spawn_lagrange_points(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bodies: Query<(Entity, &Name)>,
+) {
+    let text_style = TextStyle {
+        font: asset_server.load("PressStart2P-Regular.ttf"),
+        font_size: 10.0,
+        color: Color::SEA_GREEN,
+    };
+    let find = |name: &str| {
+        bodies
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(entity, _)| entity)
+    };
+
+    for &(primary_name, secondary_name, threshold) in LAGRANGE_PAIRS {
+        let (Some(primary), Some(secondary)) = (find(primary_name), find(secondary_name)) else {
+            continue;
+        };
+        for index in 1..=5 {
+            let point = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    Coord::default(),
+                    Primary(secondary),
+                    LagrangePoint {
+                        primary,
+                        secondary,
+                        index,
+                    },
+                ))
+                .id();
+            label(
+                &mut commands,
+                &text_style,
+                point,
+                &format!("L{} {}-{}", index, primary_name, secondary_name),
+                0.0,
+                (AU * SCALE * threshold) as f32,
+            );
+        }
+    }
+}
+
+/// Positions of L1 to L5 for a primary and secondary of the given masses and states.
+pub fn lagrange_points(
+    (m1, r1, v1): (f64, DVec3, DVec3),
+    (m2, r2, v2): (f64, DVec3, DVec3),
+) -> [DVec3; 5] {
+    let separation = r2 - r1;
+    let d = separation.length();
+    let x_axis = separation / d;
+    let normal = separation.cross(v2 - v1).normalize();
+    let y_axis = normal.cross(x_axis);
+    let mu = m2 / (m1 + m2);
+    let barycenter = r1 + separation * mu;
+    let at = |x: f64, y: f64| barycenter + (x_axis * x + y_axis * y) * d;
+
+    // Collinear points: roots of the rotating-frame force along the axis, in
+    // units of the separation and measured from the barycenter
+    let collinear = |guess: f64| {
+        let mut x = guess;
+        for _ in 0..50 {
+            let (a, b) = (x + mu, x - 1.0 + mu);
+            let f = x - (1.0 - mu) * a / a.abs().powi(3) - mu * b / b.abs().powi(3);
+            let df = 1.0 + 2.0 * (1.0 - mu) / a.abs().powi(3) + 2.0 * mu / b.abs().powi(3);
+            let step = f / df;
+            x -= step;
+            if step.abs() < 1e-15 {
+                break;
+            }
+        }
+        x
+    };
+    let hill = (mu / 3.0).cbrt();
+
+    [
+        at(collinear(1.0 - mu - hill), 0.0),
+        at(collinear(1.0 - mu + hill), 0.0),
+        at(collinear(-1.0 - 5.0 * mu / 12.0), 0.0),
+        at(0.5 - mu, 3f64.sqrt() / 2.0),
+        at(0.5 - mu, -(3f64.sqrt()) / 2.0),
+    ]
+}
+
+/// State of a body co-rotating with the pair at a point of their rotating frame.
+fn corotating_velocity(
+    (m1, r1, v1): (f64, DVec3, DVec3),
+    (m2, r2, v2): (f64, DVec3, DVec3),
+    point: DVec3,
+) -> DVec3 {
+    let separation = r2 - r1;
+    let omega = separation.cross(v2 - v1) / separation.length_squared();
+    let mu = m2 / (m1 + m2);
+    let barycenter = r1 + separation * mu;
+    let barycenter_velocity = v1 + (v2 - v1) * mu;
+    barycenter_velocity + omega.cross(point - barycenter)
+}
+
+pub fn update_lagrange_points(
+    settings: Res<LagrangeSettings>,
+    bodies: Query<(&Mass, &Coord, &Velocity), Without<LagrangePoint>>,
+    mut points: Query<(&LagrangePoint, &mut Coord, &mut Transform)>,
+    mut labels: Query<(&Label, &mut Visibility)>,
+) {
+    for (point, mut coord, mut transform) in &mut points {
+        let (Ok(primary), Ok(secondary)) = (bodies.get(point.primary), bodies.get(point.secondary))
+        else {
+            continue;
+        };
+        coord.0 = lagrange_points(state(primary), state(secondary))[point.index - 1];
+        transform.translation = (coord.0 * SCALE).as_vec3();
+    }
+
+    for (label, mut visibility) in &mut labels {
+        if points.contains(label.entity) {
+            *visibility = if settings.visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+fn state((mass, coord, velocity): (&Mass, &Coord, &Velocity)) -> (f64, DVec3, DVec3) {
+    (mass.0, coord.0, velocity.0)
+}
+
+pub fn draw_lagrange_points(
+    mut gizmos: Gizmos,
+    settings: Res<LagrangeSettings>,
+    points: Query<(&LagrangePoint, &Transform)>,
+    camera: Query<&Transform, With<Camera>>,
+) {
+    if !settings.visible {
+        return;
+    }
+    let camera_transform = camera.single();
+    let normal = camera_transform.rotation * Vec3::Z;
+
+    for (i, (_, transform)) in points.iter().enumerate() {
+        let size = (camera_transform.translation - transform.translation).length() * 0.006;
+        let color = if i == settings.selected {
+            Color::YELLOW
+        } else {
+            Color::SEA_GREEN
+        };
+        let right = camera_transform.rotation * Vec3::X * size;
+        let up = camera_transform.rotation * Vec3::Y * size;
+        gizmos.circle(transform.translation, normal, size, color);
+        gizmos.line(
+            transform.translation - right,
+            transform.translation + right,
+            color,
+        );
+        gizmos.line(
+            transform.translation - up,
+            transform.translation + up,
+            color,
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn control_lagrange_points(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: ResMut<LagrangeSettings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    bodies: Query<(&Mass, &Coord, &Velocity, &Name), Without<LagrangePoint>>,
+    points: Query<(&LagrangePoint, &Coord)>,
+) {
+    if keyboard_input.just_pressed(KeyCode::G) {
+        settings.visible = !settings.visible;
+    }
+    if keyboard_input.just_pressed(KeyCode::N) {
+        settings.selected = (settings.selected + 1) % points.iter().len().max(1);
+    }
+
+    if !keyboard_input.just_pressed(KeyCode::T) {
+        return;
+    }
+    let Some((point, coord)) = points.iter().nth(settings.selected) else {
+        return;
+    };
+    let (Ok(primary), Ok(secondary)) = (bodies.get(point.primary), bodies.get(point.secondary))
+    else {
+        return;
+    };
+    let velocity = corotating_velocity(
+        state((primary.0, primary.1, primary.2)),
+        state((secondary.0, secondary.1, secondary.2)),
+        coord.0,
+    );
+    let name = unique_name(
+        format!("L{} {}-{} particle", point.index, primary.3, secondary.3),
+        bodies.iter().map(|body| body.3.as_str()),
+    );
+    spawn_particle(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &asset_server,
+        &name,
+        point.primary,
+        coord.0,
+        velocity,
+    );
+}
+
+/// Spawns a test particle at the given absolute state, orbiting `primary`.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_particle(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    asset_server: &AssetServer,
+    name: &str,
+    primary: Entity,
+    coord: DVec3,
+    velocity: DVec3,
+) -> Entity {
+    let particle = commands
+        .spawn((
+            BodyBundle {
+                pbr: PbrBundle {
+                    mesh: meshes.add(Mesh::from(shape::UVSphere {
+                        radius: 0.002,
+                        sectors: 8,
+                        stacks: 4,
+                    })),
+                    material: materials.add(StandardMaterial {
+                        base_color: Color::YELLOW,
+                        unlit: true,
+                        ..default()
+                    }),
+                    transform: Transform::from_translation((coord * SCALE).as_vec3()),
+                    ..default()
+                },
+                mass: Mass(TEST_PARTICLE_MASS),
+                velocity: Velocity(velocity),
+                coord: Coord(coord),
+                circle_size: CircleSize(0.004),
+                ..default()
+            },
+            Kind::Particle,
+            Primary(primary),
+            Name::new(name.to_string()),
+        ))
+        .id();
+
+    label(
+        commands,
+        &TextStyle {
+            font: asset_server.load("PressStart2P-Regular.ttf"),
+            font_size: 12.0,
+            color: Color::ANTIQUE_WHITE,
+        },
+        particle,
+        &format!("{}: {}", Kind::Particle.title(), name),
+        0.0,
+        (AU * SCALE) as f32,
+    );
+    particle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{setup, tests::headless_app};
+
+    #[test]
+    fn earth_sun_l1_and_l2_are_a_hundredth_of_an_au_from_earth() {
+        let speed = (G * (SUN_MASS + EARTH_MASS) / AU).sqrt();
+        let earth = (EARTH_MASS, DVec3::X * AU, DVec3::Y * speed);
+        let points = lagrange_points((SUN_MASS, DVec3::ZERO, DVec3::ZERO), earth);
+        for point in &points[..2] {
+            let distance = point.distance(earth.1) / AU;
+            assert!((distance - 0.01).abs() < 0.001, "{} AU", distance);
+        }
+        assert!(points[0].x < AU && points[1].x > AU);
+    }
+
+    #[test]
+    fn startup_places_every_lagrange_point() {
+        let mut app = headless_app();
+        app.add_systems(
+            Startup,
+            (setup, apply_deferred, spawn_lagrange_points).chain(),
+        );
+        app.update();
+        let world = &mut app.world;
+        let points = world.query::<&LagrangePoint>().iter(world).count();
+        assert_eq!(points, LAGRANGE_PAIRS.len() * 5);
+    }
+}
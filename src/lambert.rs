@@ -0,0 +1,91 @@
+use std::f64::consts::PI;
+
+use bevy::math::DVec3;
+
+/// Stumpff functions C(z) and S(z) of the universal variable formulation.
+pub fn stumpff(z: f64) -> (f64, f64) {
+    if z > 1e-6 {
+        let s = z.sqrt();
+        ((1.0 - s.cos()) / z, (s - s.sin()) / (s * z))
+    } else if z < -1e-6 {
+        let s = (-z).sqrt();
+        ((s.cosh() - 1.0) / -z, (s.sinh() - s) / (s * -z))
+    } else {
+        // Series near zero, where the closed forms lose all precision
+        (0.5 - z / 24.0, 1.0 / 6.0 - z / 120.0)
+    }
+}
+
+/// Velocities at departure and arrival of the single-revolution conic around
+/// a body of gravitational parameter `mu` going from `r1` to `r2` in `time`
+/// seconds, counterclockwise about +Z when `prograde`.
+pub fn lambert(r1: DVec3, r2: DVec3, time: f64, mu: f64, prograde: bool) -> Option<(DVec3, DVec3)> {
+    let (r1n, r2n) = (r1.length(), r2.length());
+    let mut angle = (r1.dot(r2) / (r1n * r2n)).clamp(-1.0, 1.0).acos();
+    if (r1.cross(r2).z < 0.0) == prograde {
+        angle = 2.0 * PI - angle;
+    }
+    let a = angle.sin() * (r1n * r2n / (1.0 - angle.cos())).sqrt();
+    if !a.is_finite() || a == 0.0 {
+        // Transfer angle of 0 or 180 degrees: the plane is undetermined
+        return None;
+    }
+
+    let y = |z: f64| {
+        let (c, s) = stumpff(z);
+        r1n + r2n + a * (z * s - 1.0) / c.sqrt()
+    };
+    // Grows with z from zero, where y vanishes or z goes to minus infinity,
+    // to infinity at the single revolution limit
+    let time_error = |z: f64| {
+        let (c, s) = stumpff(z);
+        let y = y(z);
+        if y < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        ((y / c).powf(1.5) * s + a * y.sqrt()) / mu.sqrt() - time
+    };
+
+    let (mut low, mut high) = (-4.0 * PI * PI, 4.0 * PI * PI * (1.0 - 1e-9));
+    while time_error(low) > 0.0 {
+        low *= 2.0;
+        if low < -1e5 {
+            return None;
+        }
+    }
+    if time_error(high) < 0.0 {
+        return None;
+    }
+    for _ in 0..100 {
+        let z = (low + high) / 2.0;
+        if time_error(z) < 0.0 {
+            low = z;
+        } else {
+            high = z;
+        }
+    }
+
+    let y = y((low + high) / 2.0);
+    let f = 1.0 - y / r1n;
+    let g = a * (y / mu).sqrt();
+    let g_dot = 1.0 - y / r2n;
+    Some(((r2 - r1 * f) / g, (r2 * g_dot - r1) / g))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::*, kepler::propagate};
+
+    #[test]
+    fn lambert_velocity_reaches_the_target_in_time() {
+        let mu = G * SUN_MASS;
+        let r1 = DVec3::new(AU, 0.0, 0.0);
+        let r2 = DVec3::new(-0.5 * AU, 1.2 * AU, 0.01 * AU);
+        let time = 150.0 * 86400.0;
+        let (v1, v2) = lambert(r1, r2, time, mu, true).unwrap();
+        let (r, v) = propagate(r1, v1, mu, time);
+        assert!(r.distance(r2) < 1e-6 * AU, "{} m off", r.distance(r2));
+        assert!(v.distance(v2) < 1e-3, "{} m/s off", v.distance(v2));
+    }
+}
@@ -1,18 +1,65 @@
+pub mod approaches;
+pub mod bodies;
+pub mod comets;
 pub mod constants;
-
-use bevy::{math::DVec3, prelude::*};
+pub mod events;
+pub mod export;
+pub mod forces;
+pub mod influence;
+pub mod kepler;
+pub mod lagrange;
+pub mod lambert;
+pub mod lighting;
+pub mod maneuver;
+pub mod oblateness;
+pub mod osculating;
+pub mod patched;
+pub mod porkchop;
+pub mod prediction;
+pub mod reference;
+pub mod relativity;
+pub mod replay;
+pub mod rings;
+pub mod scenarios;
+pub mod snapshot;
+pub mod spacecraft;
+pub mod textures;
+pub mod transfer;
+
+use bevy::{
+    ecs::schedule::ScheduleLabel, math::DVec3, pbr::NotShadowCaster, prelude::*, utils::HashMap,
+};
 use bevy_panorbit_camera::PanOrbitCamera;
 use constants::*;
+use lighting::{blackbody, luminosity, star_light, Luminosity, StarShadows};
+use osculating::OrbitDisplay;
+use patched::Propagation;
+use scenarios::Scenario;
+use serde::{Deserialize, Serialize};
+use textures::BodyTextures;
+
+/// One timestep and the detectors sampling it, run once per `FixedUpdate`
+/// times the time warp.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Step;
+
+/// Integration substep, run `SUBSTEPS` times per timestep.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Physics;
 
 #[derive(Component, Default)]
 pub struct Coord(DVec3);
 
 #[derive(Component, Default)]
-pub struct Velocity(Vec3);
+pub struct Velocity(DVec3);
 
 #[derive(Component, Default)]
 pub struct Mass(f64);
 
+/// Mean radius in meters.
+#[derive(Component, Default)]
+pub struct Radius(f64);
+
 #[derive(Component, Default)]
 pub struct Ordinal(usize);
 
@@ -22,21 +69,86 @@ pub struct Trajectory(Vec<DVec3>);
 #[derive(Component, Default)]
 pub struct CircleSize(f32);
 
+/// The body this one orbits; its initial state is given relative to it.
+#[derive(Component)]
+pub struct Primary(Entity);
+
+/// Orientation of the rotation axis and sidereal rotation period in seconds.
+#[derive(Component)]
+pub struct Spin {
+    pole: Quat,
+    period: f64,
+}
+
+impl Spin {
+    /// Body meshes have their poles on the local Z axis, like the ecliptic normal;
+    /// this tilts it by `tilt` towards `azimuth`.
+    pub fn new(tilt: f32, azimuth: f32, period: f64) -> Self {
+        Spin {
+            pole: Quat::from_rotation_z(azimuth) * Quat::from_rotation_x(tilt),
+            period,
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct PanSoft(f32);
 
+/// Simulated seconds elapsed since the start of the run.
+#[derive(Resource, Default)]
+pub struct SimClock(pub f64);
+
 #[derive(Default, Bundle)]
 struct BodyBundle {
     pbr: PbrBundle,
     mass: Mass,
+    radius: Radius,
     velocity: Velocity,
     coord: Coord,
     trajectory: Trajectory,
     circle_size: CircleSize,
 }
 
+impl BodyBundle {
+    /// Adds the absolute position and velocity of the primary to the state of
+    /// the bundle, which the spec gives relative to the primary. Absolute here
+    /// is the frame of the spawned specs, before `setup` moves the barycenter
+    /// to the origin.
+    fn orbiting(mut self, coord: DVec3, velocity: DVec3) -> Self {
+        self.coord.0 += coord;
+        self.velocity.0 += velocity;
+        self.pbr.transform.translation = (self.coord.0 * SCALE).as_vec3();
+        self
+    }
+}
+
 #[derive(Default, Component)]
-struct Star;
+pub struct Star;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Kind {
+    Star,
+    Planet,
+    DwarfPlanet,
+    Satellite,
+    Comet,
+    Particle,
+    Spacecraft,
+}
+
+impl Kind {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Kind::Star => "Star",
+            Kind::Planet => "Planet",
+            Kind::DwarfPlanet => "Dwarf Planet",
+            Kind::Satellite => "Satellite",
+            Kind::Comet => "Comet",
+            Kind::Particle => "Test particle",
+            Kind::Spacecraft => "Spacecraft",
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct Label {
@@ -49,326 +161,18 @@ pub struct Label {
 #[derive(Component, Default)]
 pub struct Labled;
 
+#[derive(Component, Default)]
+pub struct BodyTree;
+
 pub fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
     asset_server: Res<AssetServer>,
     mut config: ResMut<GizmoConfig>,
+    scenario: Res<Scenario>,
 ) {
-    // Sun
-    let sun = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform {
-                translation: Vec3::ZERO,
-                ..default()
-            },
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (SUN_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(StandardMaterial {
-                base_color: Color::ORANGE_RED,
-                emissive: (Color::ORANGE_RED * 2.),
-                ..default()
-            }),
-            ..default()
-        },
-        mass: Mass(SUN_MASS),
-        velocity: Velocity(Vec3::ZERO),
-        coord: Coord(DVec3::ZERO),
-        circle_size: CircleSize(0.015),
-        ..default()
-    };
-
-    // Mercury
-    let mercury: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((MERCURY_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (MERCURY_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(Color::rgb(0.5, 0.5, 0.5).into()),
-            ..default()
-        },
-        mass: Mass(MERCURY_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(MERCURY_INCLINATION) * Vec3::new(0.0, MERCURY_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(MERCURY_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Venus
-    let venus: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((VENUS_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (VENUS_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(Color::rgb(0.52, 0.0, 1.0).into()),
-            ..default()
-        },
-        mass: Mass(VENUS_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(VENUS_INCLINATION) * Vec3::new(0.0, VENUS_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(VENUS_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Earth
-    let earth: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((EARTH_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (EARTH_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(Color::rgb(0.2, 0.7, 0.2).into()),
-            ..default()
-        },
-        mass: Mass(EARTH_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(EARTH_INCLINATION) * Vec3::new(0.0, EARTH_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(EARTH_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Moon
-    let moon: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz(
-                ((EARTH_DISTANCE + MOON_DISTANCE) * SCALE) as f32,
-                0.0,
-                0.0,
-            ),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (MOON_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(Color::rgb(0.2, 0.2, 0.2).into()),
-            ..default()
-        },
-        mass: Mass(MOON_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(EARTH_INCLINATION) * Vec3::new(0.0, EARTH_VEL, 0.0)
-                + Quat::from_rotation_x(MOON_INCLINATION) * Vec3::new(0.0, MOON_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(EARTH_DISTANCE + MOON_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.0075),
-        ..default()
-    };
-
-    // Mars
-    let mars: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((MARS_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (MARS_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(Color::rgb(0.83, 0.35, 0.21).into()),
-            ..default()
-        },
-        mass: Mass(MARS_MASS),
-        velocity: Velocity(Quat::from_rotation_x(MARS_INCLINATION) * Vec3::new(0.0, MARS_VEL, 0.0)),
-        coord: Coord(DVec3::new(MARS_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Ceres
-    let ceres: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((CERES_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (CERES_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(Color::rgb(0.7, 0.7, 0.7).into()),
-            ..default()
-        },
-        mass: Mass(CERES_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(CERES_INCLINATION) * Vec3::new(0.0, CERES_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(CERES_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Jupiter
-    let jupiter: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((JUPITER_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (JUPITER_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(StandardMaterial {
-                base_color: Color::rgb(0.76, 0.4, 0.11),
-                emissive: (Color::rgb(0.76, 0.4, 0.11) * 2.),
-                ..default()
-            }),
-            ..default()
-        },
-        mass: Mass(JUPITER_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(JUPITER_INCLINATION) * Vec3::new(0.0, JUPITER_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(JUPITER_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Saturn
-    let saturn: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((SATURN_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (SATURN_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(StandardMaterial {
-                base_color: Color::rgb(0.53, 0.45, 0.28),
-                emissive: (Color::rgb(0.53, 0.45, 0.28) * 2.),
-                ..default()
-            }),
-            ..default()
-        },
-        mass: Mass(SATURN_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(SATURN_INCLINATION) * Vec3::new(0.0, SATURN_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(SATURN_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Uranus
-    let uranus: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((URANUS_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (URANUS_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(StandardMaterial {
-                base_color: Color::rgb(0.0, 0.78, 0.78),
-                emissive: (Color::rgb(0.0, 0.78, 0.78) * 2.),
-                ..default()
-            }),
-            ..default()
-        },
-        mass: Mass(URANUS_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(URANUS_INCLINATION) * Vec3::new(0.0, URANUS_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(URANUS_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Neptune
-    let neptune: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((NEPTUNE_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (NEPTUNE_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(StandardMaterial {
-                base_color: Color::rgb(0.02, 0.26, 0.82),
-                emissive: (Color::rgb(0.02, 0.26, 0.82) * 2.),
-                ..default()
-            }),
-            ..default()
-        },
-        mass: Mass(NEPTUNE_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(NEPTUNE_INCLINATION) * Vec3::new(0.0, NEPTUNE_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(NEPTUNE_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    // Pluto
-    let pluto: BodyBundle = BodyBundle {
-        pbr: PbrBundle {
-            transform: Transform::from_xyz((PLUTO_DISTANCE * SCALE) as f32, 0.0, 0.0),
-            mesh: meshes.add(
-                Mesh::try_from(shape::Icosphere {
-                    radius: (PLUTO_RADIUS * SCALE) as f32,
-                    subdivisions: 10,
-                })
-                .unwrap(),
-            ),
-            material: materials.add(Color::rgb(0.69, 0.55, 0.43).into()),
-            ..default()
-        },
-        mass: Mass(PLUTO_MASS),
-        velocity: Velocity(
-            Quat::from_rotation_x(PLUTO_INCLINATION) * Vec3::new(0.0, PLUTO_VEL, 0.0),
-        ),
-        coord: Coord(DVec3::new(PLUTO_DISTANCE, 0.0, 0.0)),
-        circle_size: CircleSize(0.01),
-        ..default()
-    };
-
-    let sun = commands.spawn((sun, Star)).id();
-    let mercury = commands.spawn(mercury).id();
-    let venus = commands.spawn(venus).id();
-    let earth = commands.spawn(earth).id();
-    let moon = commands.spawn(moon).id();
-    let mars = commands.spawn(mars).id();
-    let ceres = commands.spawn(ceres).id();
-    let jupiter = commands.spawn(jupiter).id();
-    let saturn = commands.spawn(saturn).id();
-    let uranus = commands.spawn(uranus).id();
-    let neptune = commands.spawn(neptune).id();
-    let pluto = commands.spawn(pluto).id();
-
     // Label
     let font = asset_server.load("PressStart2P-Regular.ttf");
     let text_style = TextStyle {
@@ -377,63 +181,148 @@ pub fn setup(
         color: Color::ANTIQUE_WHITE,
     };
 
-    let mut label = |entity: Entity, label: &str, shift: f32, threshold: f32| {
-        commands
-            .spawn((
-                NodeBundle {
-                    style: Style {
-                        position_type: PositionType::Absolute,
+    // Bodies
+    let mut spawned: HashMap<&str, (Entity, DVec3, DVec3)> = HashMap::new();
+    for spec in scenario.bodies {
+        let radius = (spec.radius * SCALE) as f32;
+        let mut mesh = Mesh::from(shape::UVSphere {
+            radius,
+            sectors: 64,
+            stacks: 32,
+        });
+        mesh.generate_tangents().unwrap();
+        let star = spec.kind == Kind::Star;
+        let color = if star {
+            spec.color * blackbody(spec.temperature).as_rgba_f32()
+        } else {
+            spec.color
+        };
+        let textures =
+            BodyTextures::load(spec.name, spec.surface, color, &asset_server, &mut images);
+        let (emissive, emissive_texture) = match (&textures.night, star) {
+            (Some(night), _) => (Color::WHITE, Some(night.clone())),
+            (None, true) => (Color::WHITE * 2., Some(textures.albedo.clone())),
+            (None, false) => (Color::BLACK, None),
+        };
+
+        let mut body = BodyBundle {
+            pbr: PbrBundle {
+                mesh: meshes.add(mesh),
+                material: materials.add(StandardMaterial {
+                    base_color_texture: Some(textures.albedo.clone()),
+                    normal_map_texture: Some(textures.normal.clone()),
+                    emissive,
+                    emissive_texture,
+                    // Stars are lit from the inside by their own point light
+                    unlit: star,
+                    ..default()
+                }),
+                ..default()
+            },
+            mass: Mass(spec.mass),
+            radius: Radius(spec.radius),
+            velocity: Velocity(spec.initial_velocity()),
+            coord: Coord(DVec3::new(spec.distance, 0.0, 0.0)),
+            circle_size: CircleSize(spec.circle_size),
+            ..default()
+        };
+        body.pbr.transform = Transform::from_translation((body.coord.0 * SCALE).as_vec3());
+
+        let primary = spec.primary.map(|name| spawned[name]);
+        if let Some((_, coord, velocity)) = primary {
+            body = body.orbiting(coord, velocity);
+        }
+        let (coord, velocity) = (body.coord.0, body.velocity.0);
+
+        let mut entity = commands.spawn((
+            body,
+            spec.kind,
+            Name::new(spec.name),
+            Spin::new(spec.axial_tilt, spec.pole_azimuth, spec.rotation_period),
+        ));
+        if let Some((primary, ..)) = primary {
+            entity.insert(Primary(primary));
+        }
+        if star {
+            entity
+                .insert((
+                    Star,
+                    Luminosity(luminosity(spec.radius, spec.temperature)),
+                    NotShadowCaster,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(star_light(spec.radius, spec.temperature));
+                });
+        }
+        if let Some(clouds) = textures.clouds {
+            entity.with_children(|parent| {
+                parent.spawn(PbrBundle {
+                    mesh: meshes.add(Mesh::from(shape::UVSphere {
+                        radius: radius * 1.01,
+                        sectors: 64,
+                        stacks: 32,
+                    })),
+                    material: materials.add(StandardMaterial {
+                        base_color_texture: Some(clouds),
+                        alpha_mode: AlphaMode::Blend,
                         ..default()
-                    },
+                    }),
                     ..default()
-                },
-                Label {
-                    shift,
-                    text: label.to_string(),
-                    entity,
-                    threshold,
-                },
-            ))
-            .with_children(|parent| {
-                parent.spawn((
-                    TextBundle::from_section("", text_style.clone())
-                        .with_style(Style {
-                            position_type: PositionType::Absolute,
-                            bottom: Val::ZERO,
-                            ..default()
-                        })
-                        .with_no_wrap(),
-                    Labled,
-                ));
+                });
             });
-    };
+        }
+        let entity = entity.id();
+        spawned.insert(spec.name, (entity, coord, velocity));
+
+        label(
+            &mut commands,
+            &text_style,
+            entity,
+            &format!("{}: {}", spec.kind.title(), spec.name),
+            spec.label_shift,
+            (AU * SCALE * spec.label_threshold) as f32,
+        );
+    }
 
-    label(sun, "Star: Sun", 6.0, (AU * SCALE * 150.0) as f32);
-    label(mercury, "Planet: Mercury", 0.02, (AU * SCALE * 10.0) as f32);
-    label(venus, "Planet: Venus", 0.05, (AU * SCALE * 10.0) as f32);
-    label(earth, "Planet: Earth", 0.05, (AU * SCALE * 10.0) as f32);
-    label(moon, "Satellite: Moon", 0.01, (AU * SCALE * 0.1) as f32);
-    label(mars, "Planet: Mars", 0.03, (AU * SCALE * 10.0) as f32);
-    label(
-        ceres,
-        "Dwarf Planet: Ceres",
-        0.005,
-        (AU * SCALE * 100.0) as f32,
-    );
-    label(jupiter, "Planet: Jupiter", 0.7, (AU * SCALE * 100.0) as f32);
-    label(saturn, "Planet: Saturn", 0.6, (AU * SCALE * 100.0) as f32);
-    label(uranus, "Planet: Uranus", 0.5, (AU * SCALE * 100.0) as f32);
-    label(neptune, "Planet: Neptune", 0.5, (AU * SCALE * 100.0) as f32);
-    label(
-        pluto,
-        "Dwarf Planet: Pluto",
-        0.01,
-        (AU * SCALE * 100.0) as f32,
+    // Primaries start at rest while everything around them moves; moving
+    // the barycenter to the origin and removing the net momentum keeps the
+    // simulation in the barycentric frame
+    let (moment, momentum, mass) = scenario.bodies.iter().fold(
+        (DVec3::ZERO, DVec3::ZERO, 0.0),
+        |(moment, momentum, mass), spec| {
+            let (_, coord, velocity) = spawned[spec.name];
+            (
+                moment + coord * spec.mass,
+                momentum + velocity * spec.mass,
+                mass + spec.mass,
+            )
+        },
     );
+    if mass > 0.0 {
+        let (center, drift) = (moment / mass, momentum / mass);
+        for &(entity, coord, velocity) in spawned.values() {
+            commands.entity(entity).insert((
+                Coord(coord - center),
+                Velocity(velocity - drift),
+                Transform::from_translation(((coord - center) * SCALE).as_vec3()),
+            ));
+        }
+    }
+
+    // Body tree
+    commands.spawn((
+        TextBundle::from_section("", text_style.clone()).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        BodyTree,
+    ));
 
     // Camera
-    let position =
-        Transform::from_xyz(0.0, 0.0, (AU * 3.0 * SCALE) as f32).looking_at(Vec3::ZERO, Vec3::Y);
+    let view_distance = (AU * scenario.view_distance * SCALE) as f32;
+    let position = Transform::from_xyz(0.0, 0.0, view_distance).looking_at(Vec3::ZERO, Vec3::Y);
     commands.spawn((
         Camera3dBundle {
             transform: position,
@@ -441,7 +330,7 @@ pub fn setup(
         },
         Ordinal(0),
         PanOrbitCamera {
-            radius: Some((AU * 3.0 * SCALE) as f32),
+            radius: Some(view_distance),
             pan_smoothness: 0.0,
             ..default()
         },
@@ -450,42 +339,76 @@ pub fn setup(
     config.line_width = 2.0;
 
     commands.init_resource::<PanSoft>();
+    commands.init_resource::<StarShadows>();
 }
 
-pub fn attraction(_time: Res<Time>, mut query: Query<(&Mass, &mut Velocity, &Coord)>) {
-    let mut iter = query.iter_combinations_mut();
-    while let Some([(Mass(m1), mut vel1, coord1), (Mass(m2), mut vel2, coord2)]) = iter.fetch_next()
-    {
-        let diff1 = coord2.0 - coord1.0;
-        let distance = diff1.length();
-
-        let phi1 = diff1.y.atan2(diff1.x);
-        let theta1 = (diff1.z / distance).acos();
-
-        let force1 = (G * m2) / (distance * distance);
-
-        let x1 = force1 * theta1.sin() * phi1.cos();
-        let y1 = force1 * theta1.sin() * phi1.sin();
-        let z1 = force1 * theta1.cos();
-
-        let force1 = DVec3::new(x1, y1, z1);
-
-        vel1.0 += force1.as_vec3() * TIMESTEP;
-
-        let diff2 = coord1.0 - coord2.0;
+fn label(
+    commands: &mut Commands,
+    text_style: &TextStyle,
+    entity: Entity,
+    label: &str,
+    shift: f32,
+    threshold: f32,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+            Label {
+                shift,
+                text: label.to_string(),
+                entity,
+                threshold,
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", text_style.clone())
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::ZERO,
+                        ..default()
+                    })
+                    .with_no_wrap(),
+                Labled,
+            ));
+        });
+}
 
-        let phi2 = diff2.y.atan2(diff2.x);
-        let theta2 = (diff2.z / distance).acos();
+/// Runs the `Step` schedule once per fixed timestep times the time warp, so
+/// events are looked for every timestep however fast time goes.
+pub fn run_steps(world: &mut World) {
+    let warp = world.get_resource::<Propagation>().map_or(1, |p| p.warp);
+    for _ in 0..warp {
+        world.run_schedule(Step);
+    }
+}
 
-        let force2 = (G * m1) / (distance * distance);
+/// Runs the `Physics` schedule `SUBSTEPS` times per timestep.
+pub fn run_physics(world: &mut World) {
+    for _ in 0..SUBSTEPS {
+        world.run_schedule(Physics);
+    }
+}
 
-        let x2 = force2 * theta2.sin() * phi2.cos();
-        let y2 = force2 * theta2.sin() * phi2.sin();
-        let z2 = force2 * theta2.cos();
+pub fn attraction(mut query: Query<(&Mass, &mut Velocity, &Coord)>) {
+    let mut iter = query.iter_combinations_mut();
+    while let Some([(Mass(m1), mut vel1, coord1), (Mass(m2), mut vel2, coord2)]) = iter.fetch_next()
+    {
+        let diff = coord2.0 - coord1.0;
+        let distance_squared = diff.length_squared();
+        let direction = diff.normalize();
 
-        let force2 = DVec3::new(x2, y2, z2);
+        let force1 = direction * (G * m2) / distance_squared;
+        vel1.0 += force1 * DT as f64;
 
-        vel2.0 += force2.as_vec3() * TIMESTEP;
+        let force2 = -direction * (G * m1) / distance_squared;
+        vel2.0 += force2 * DT as f64;
     }
 }
 
@@ -494,18 +417,33 @@ pub fn update_pansoft(mut pansoft: ResMut<PanSoft>) {
     pansoft.0 = pansoft.0.max(0.0);
 }
 
-pub fn update_position(
-    mut bodies: Query<(&Velocity, &mut Transform, &mut Coord, &mut Trajectory)>,
-) {
-    for (vel, mut transform, mut coord, mut trajectory) in &mut bodies {
-        coord.0 += (vel.0 * TIMESTEP).as_dvec3(); //* time.delta_seconds();
+pub fn update_position(mut bodies: Query<(&Velocity, &mut Transform, &mut Coord)>) {
+    for (vel, mut transform, mut coord) in &mut bodies {
+        coord.0 += vel.0 * DT as f64;
         transform.translation = (coord.0 * SCALE).as_vec3();
+    }
+}
+
+pub fn advance_clock(mut clock: ResMut<SimClock>) {
+    clock.0 += DT as f64;
+}
+
+pub fn update_rotation(clock: Res<SimClock>, mut bodies: Query<(&Spin, &mut Transform)>) {
+    for (spin, mut transform) in &mut bodies {
+        let angle = (clock.0 / spin.period).fract() * std::f64::consts::TAU;
+        transform.rotation = spin.pole * Quat::from_rotation_z(angle as f32);
+    }
+}
+
+pub fn record_trajectory(mut bodies: Query<(&Coord, &mut Trajectory)>) {
+    for (coord, mut trajectory) in &mut bodies {
         trajectory.0.push(coord.0);
     }
 }
 
 pub fn draw_gizmos(
     mut gizmos: Gizmos,
+    display: Res<OrbitDisplay>,
     bodies: Query<(&Coord, &Trajectory, &CircleSize, &Transform)>,
     camera: Query<&Transform, With<Camera>>,
 ) {
@@ -521,6 +459,9 @@ pub fn draw_gizmos(
             Color::rgb(0.3, 0.0, 0.0),
         );
 
+        if !display.trails() {
+            continue;
+        }
         for coords in trajectory.0.windows(2) {
             gizmos.line(
                 (coords[0] * SCALE).as_vec3(),
@@ -584,15 +525,19 @@ pub fn switch_focus_body(
 
 pub fn update_labels(
     mut labels: Query<(&mut Style, &Label)>,
-    bodies: Query<&GlobalTransform>,
+    bodies: Query<(&GlobalTransform, &Coord, Option<&Primary>)>,
+    primaries: Query<&Coord>,
     mut camera: Query<(&mut Camera, &GlobalTransform)>,
     mut label_text: Query<&mut Text, With<Labled>>,
 ) {
     let (camera, camera_global_transform) = camera.single_mut();
 
     for ((mut style, label), mut text) in &mut labels.iter_mut().zip(&mut label_text) {
-        let body_transform = bodies.get(label.entity).unwrap();
-        let dist = body_transform.translation().length();
+        let (body_transform, coord, primary) = bodies.get(label.entity).unwrap();
+        let dist = match primary {
+            Some(Primary(primary)) => coord.0.distance(primaries.get(*primary).unwrap().0),
+            None => coord.0.length(),
+        };
         let world_position =
             body_transform.translation() + Vec3::new(label.shift, -label.shift, 0.0);
 
@@ -605,10 +550,116 @@ pub fn update_labels(
             let dist_camera = (camera_global_transform.translation() - world_position).length();
 
             text.sections[0].value = if dist_camera <= label.threshold {
-                format!("{}\n{:.4} AU", label.text, dist / (AU * SCALE) as f32)
+                format!("{}\n{}", label.text, format_distance(dist))
             } else {
                 "".to_string()
             };
         }
     }
 }
+
+pub fn update_body_tree(
+    mut tree: Query<&mut Text, With<BodyTree>>,
+    bodies: Query<(Entity, &Name, &Coord, Option<&Primary>), With<Mass>>,
+    camera: Query<&Ordinal>,
+) {
+    let ordinal = camera.single();
+    let focus = bodies.iter().nth(ordinal.0).map(|(entity, ..)| entity);
+
+    let mut lines = Vec::new();
+    for (entity, ..) in bodies.iter().filter(|(.., primary)| primary.is_none()) {
+        push_subtree(&bodies, entity, 0, focus, &mut lines);
+    }
+
+    tree.single_mut().sections[0].value = lines.join("\n");
+}
+
+fn push_subtree(
+    bodies: &Query<(Entity, &Name, &Coord, Option<&Primary>), With<Mass>>,
+    entity: Entity,
+    depth: usize,
+    focus: Option<Entity>,
+    lines: &mut Vec<String>,
+) {
+    let (_, name, coord, primary) = bodies.get(entity).unwrap();
+    let marker = if focus == Some(entity) { ">" } else { " " };
+    let line = match primary.and_then(|Primary(primary)| bodies.get(*primary).ok()) {
+        Some((_, _, primary_coord, _)) => format!(
+            "{}{}{} {}",
+            marker,
+            "  ".repeat(depth),
+            name,
+            format_distance(coord.0.distance(primary_coord.0))
+        ),
+        None => format!("{}{}{}", marker, "  ".repeat(depth), name),
+    };
+    lines.push(line);
+
+    for (child, ..) in bodies
+        .iter()
+        .filter(|(.., primary)| matches!(primary, Some(Primary(primary)) if *primary == entity))
+    {
+        push_subtree(bodies, child, depth + 1, focus, lines);
+    }
+}
+
+/// Formats simulated seconds as days and time of day since the start of the run.
+pub fn format_time(seconds: f64) -> String {
+    let sign = if seconds < 0.0 { "-" } else { "+" };
+    let seconds = seconds.abs() as u64;
+    format!(
+        "T{}{}d {:02}:{:02}",
+        sign,
+        seconds / 86400,
+        seconds % 86400 / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+/// Formats a distance in meters, switching to km below a hundredth of an AU.
+pub fn format_distance(meters: f64) -> String {
+    if meters < AU * 0.01 {
+        format!("{:.0} km", meters / 1e3)
+    } else {
+        format!("{:.4} AU", meters / AU)
+    }
+}
+
+/// Value following `flag` on the command line, if it was given.
+pub fn argument(flag: &str) -> Option<String> {
+    std::env::args().skip_while(|arg| arg != flag).nth(1)
+}
+
+/// `name`, numbered from 2 if one of the `taken` names already is; bodies are
+/// told apart by name in snapshots and recordings.
+pub fn unique_name<'a>(name: String, taken: impl Iterator<Item = &'a str>) -> String {
+    let taken: Vec<_> = taken.collect();
+    if !taken.contains(&name.as_str()) {
+        return name;
+    }
+    (2..)
+        .map(|number| format!("{} {}", name, number))
+        .find(|numbered| !taken.contains(&numbered.as_str()))
+        .unwrap()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use bevy::{asset::AssetPlugin, prelude::*};
+
+    use crate::scenarios::Scenario;
+
+    /// An app without a window that has what `setup` and the systems
+    /// spawning alongside it need.
+    pub fn headless_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .init_asset::<Image>()
+            .init_asset::<Font>()
+            .init_resource::<GizmoConfig>()
+            .init_resource::<Scenario>();
+        app
+    }
+}
@@ -0,0 +1,105 @@
+use bevy::{
+    prelude::*,
+    render::view::{ColorGrading, RenderLayers},
+};
+
+use crate::{constants::*, rings::SHADOW_LAYER, Coord, Mass, Ordinal, Star};
+
+/// Whether star lights cast shadows, toggled with `L`.
+#[derive(Resource, Default)]
+pub struct StarShadows(pub bool);
+
+/// Power a star radiates, relative to the Sun.
+#[derive(Component)]
+pub struct Luminosity(pub f64);
+
+/// Luminosity of a star of the given radius in meters and effective
+/// temperature in kelvin, relative to the Sun.
+pub fn luminosity(radius: f64, temperature: f64) -> f64 {
+    (radius / SUN_RADIUS).powi(2) * (temperature / SUN_TEMPERATURE).powi(4)
+}
+
+/// Approximate color of a black body at the given temperature in kelvin,
+/// after Tanner Helland's fit to the CIE 1964 color matching functions.
+pub fn blackbody(temperature: f64) -> Color {
+    let t = (temperature / 100.0).clamp(10.0, 400.0);
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * (t - 60.0).powf(-0.1332047592)
+    };
+    let green = if t <= 66.0 {
+        99.4708025861 * t.ln() - 161.1195681661
+    } else {
+        288.1221695283 * (t - 60.0).powf(-0.0755148492)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * (t - 10.0).ln() - 305.0447927307
+    };
+    let channel = |value: f64| (value / 255.0).clamp(0.0, 1.0) as f32;
+    Color::rgb(channel(red), channel(green), channel(blue))
+}
+
+/// Point light placed at the center of a star of the given radius in meters
+/// and effective temperature in kelvin, colored and as bright as the star.
+/// It also sees the shadow layer, so the rings there cast shadows.
+pub fn star_light(radius: f64, temperature: f64) -> (PointLightBundle, RenderLayers) {
+    let light = PointLightBundle {
+        point_light: PointLight {
+            color: blackbody(temperature),
+            intensity: SUN_LIGHT_INTENSITY * luminosity(radius, temperature) as f32,
+            range: LIGHT_RANGE,
+            radius: (radius * SCALE) as f32,
+            ..default()
+        },
+        ..default()
+    };
+    (light, RenderLayers::layer(0).with(SHADOW_LAYER))
+}
+
+pub fn toggle_shadows(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut shadows: ResMut<StarShadows>,
+    mut lights: Query<&mut PointLight>,
+) {
+    if keyboard_input.just_pressed(KeyCode::L) {
+        shadows.0 = !shadows.0;
+        for mut light in &mut lights {
+            light.shadows_enabled = shadows.0;
+        }
+    }
+}
+
+/// Light falls off with the square of the distance, so without compensation
+/// everything past Mars is nearly black. Exposure is raised so the focused body
+/// receives as much light, from all stars together, as the Earth does from the
+/// Sun.
+pub fn update_exposure(
+    mut camera: Query<(&Ordinal, &mut ColorGrading)>,
+    bodies: Query<&Coord, With<Mass>>,
+    stars: Query<(&Coord, &Luminosity), With<Star>>,
+) {
+    let (ordinal, mut grading) = camera.single_mut();
+    let Some(focus) = bodies.iter().nth(ordinal.0) else {
+        return;
+    };
+
+    // Relative to the flux at 1 AU from the Sun
+    let flux: f64 = stars
+        .iter()
+        .map(|(star, luminosity)| {
+            let distance = star
+                .0
+                .distance(focus.0)
+                .max(EXPOSURE_MIN_DISTANCE * luminosity.0.sqrt());
+            luminosity.0 / (distance / AU).powi(2)
+        })
+        .sum();
+    if flux > 0.0 {
+        grading.exposure = -flux.log2() as f32;
+    }
+}
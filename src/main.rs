@@ -1,30 +1,225 @@
-use bevy::prelude::*;
+use bevy::{log::LogPlugin, pbr::PointLightShadowMap, prelude::*};
 // use nalgebra::Vector3;
-use bevy_panorbit_camera::PanOrbitCameraPlugin;
+use bevy_panorbit_camera::{PanOrbitCameraPlugin, PanOrbitCameraSystemSet};
+use solar_system_sim::approaches::{detect_approaches, ApproachDetector};
+use solar_system_sim::comets::{draw_comet_tails, spawn_comet_outgassing};
+use solar_system_sim::events::{
+    detect_eclipses, navigate_events, select_eclipse_candidates, spawn_event_panel,
+    update_event_panel, ClockJumped, EclipseDetector, EventLog, EventObserver,
+};
+use solar_system_sim::export::{
+    control_export, export_recording, sample_export, spawn_export_panel, update_export_panel,
+    ExportSettings,
+};
+use solar_system_sim::forces::{
+    spawn_force_panel, update_force_panel, ForceDiagnostics, Outgassing, RadiationPressure,
+    RegisterForce, Yarkovsky,
+};
+use solar_system_sim::influence::{
+    draw_influence_spheres, toggle_influence_overlay, InfluenceOverlay,
+};
+use solar_system_sim::lagrange::{
+    control_lagrange_points, draw_lagrange_points, spawn_lagrange_points, update_lagrange_points,
+    LagrangeSettings,
+};
+use solar_system_sim::lighting::{toggle_shadows, update_exposure};
+use solar_system_sim::maneuver::{
+    draw_maneuver, edit_maneuver, predict_maneuver, spawn_maneuver_panel, update_maneuver_panel,
+    ManeuverEditor,
+};
+use solar_system_sim::oblateness::{
+    cycle_zonal_harmonics, oblate, spawn_oblateness, zonal_attraction, ZonalHarmonics,
+};
+use solar_system_sim::osculating::{cycle_orbit_display, draw_osculating_orbits, OrbitDisplay};
+use solar_system_sim::patched::{
+    control_propagation, n_body, patched_conics, propagate_conics, spawn_propagation_panel,
+    update_propagation_panel, Propagation,
+};
+use solar_system_sim::porkchop::{plot_porkchop, spawn_porkchop_panel, Porkchop};
+use solar_system_sim::prediction::{update_ephemeris, Ephemeris};
+use solar_system_sim::reference::{
+    draw_reference_orbits, show_reference_orbits, spawn_reference_orbits, spawn_reference_panel,
+    ReferenceOrbits,
+};
+use solar_system_sim::relativity::{
+    measure_perihelion_advance, relativistic, relativistic_correction, spawn_perihelion_panel,
+    Relativity,
+};
+use solar_system_sim::replay::{
+    control_recording, control_replay, live, play_replay, record_frames, spawn_replay_panel,
+    update_replay_panel, Recorder, Replay,
+};
+use solar_system_sim::rings::spawn_rings;
+use solar_system_sim::scenarios::Scenario;
+use solar_system_sim::snapshot::{quick_save, restore_snapshot, Snapshot, Snapshots};
+use solar_system_sim::spacecraft::{fire_engines, spawn_spacecraft};
+use solar_system_sim::transfer::{calculate_transfers, spawn_transfer_panel, TransferCalculator};
 use solar_system_sim::{
-    attraction, draw_gizmos, look_at_target, setup, switch_focus_body, update_labels,
-    update_pansoft, update_position,
+    advance_clock, argument, attraction, draw_gizmos, look_at_target, record_trajectory,
+    run_physics, run_steps, setup, switch_focus_body, update_body_tree, update_labels,
+    update_pansoft, update_position, update_rotation, Physics, SimClock, Step,
 };
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
-        .add_plugins(PanOrbitCameraPlugin)
-        .insert_resource(ClearColor(Color::rgb(0.01, 0.0, 0.05)))
+    // Converting a recording needs no window, only the log to report to
+    if let Some(path) = argument("--export") {
+        App::new().add_plugins(LogPlugin::default());
+        export_recording(&path, &ExportSettings::from_args());
+        return;
+    }
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
+        .add_plugins(PanOrbitCameraPlugin);
+
+    // Read once logging is set up. A snapshot brings back the scenario it was
+    // saved in
+    let snapshot = Snapshot::from_args();
+    let scenario = snapshot
+        .as_ref()
+        .and_then(|snapshot| Scenario::find(&snapshot.scenario))
+        .unwrap_or_else(Scenario::from_args);
+
+    app.insert_resource(ClearColor(Color::rgb(0.01, 0.0, 0.05)))
         .insert_resource(AmbientLight {
-            brightness: 1.0,
+            brightness: 0.02,
+            ..default()
+        })
+        .insert_resource(PointLightShadowMap { size: 4096 })
+        .insert_resource(scenario)
+        .insert_resource(Snapshots {
+            pending: snapshot,
             ..default()
         })
-        .add_systems(Startup, setup)
-        .add_systems(FixedUpdate, (attraction, update_position, update_pansoft))
+        .insert_resource(Recorder::from_args())
+        .insert_resource(Replay::from_args())
+        .insert_resource(ExportSettings::from_args())
+        .init_resource::<SimClock>()
+        .init_resource::<EventLog>()
+        .init_resource::<EventObserver>()
+        .init_resource::<EclipseDetector>()
+        .init_resource::<ApproachDetector>()
+        .init_resource::<LagrangeSettings>()
+        .init_resource::<InfluenceOverlay>()
+        .init_resource::<Ephemeris>()
+        .init_resource::<ManeuverEditor>()
+        .init_resource::<Porkchop>()
+        .init_resource::<TransferCalculator>()
+        .init_resource::<Propagation>()
+        .init_resource::<ReferenceOrbits>()
+        .init_resource::<OrbitDisplay>()
+        .init_resource::<Relativity>()
+        .init_resource::<ZonalHarmonics>()
+        .init_resource::<ForceDiagnostics>()
+        .register_force::<RadiationPressure>()
+        .register_force::<Outgassing>()
+        .register_force::<Yarkovsky>()
+        .add_event::<ClockJumped>()
+        .add_systems(
+            Startup,
+            (
+                // The systems after setup look up the bodies it spawns
+                (
+                    setup,
+                    apply_deferred,
+                    spawn_rings,
+                    spawn_lagrange_points,
+                    spawn_spacecraft,
+                    spawn_reference_orbits,
+                    spawn_oblateness,
+                    spawn_comet_outgassing,
+                )
+                    .chain(),
+                spawn_event_panel,
+                spawn_maneuver_panel,
+                spawn_porkchop_panel,
+                spawn_transfer_panel,
+                spawn_propagation_panel,
+                spawn_reference_panel,
+                spawn_perihelion_panel,
+                spawn_force_panel,
+                spawn_replay_panel,
+                spawn_export_panel,
+            ),
+        )
+        .add_systems(
+            Physics,
+            (
+                attraction,
+                relativistic_correction.run_if(relativistic),
+                zonal_attraction.run_if(oblate),
+                fire_engines,
+                update_position,
+                advance_clock,
+            )
+                .chain(),
+        )
+        .add_systems(
+            Step,
+            (
+                run_physics.run_if(n_body),
+                propagate_conics.run_if(patched_conics),
+                detect_eclipses,
+                detect_approaches,
+                record_frames,
+                sample_export,
+            )
+                .chain(),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                (
+                    select_eclipse_candidates,
+                    run_steps,
+                    record_trajectory,
+                    update_ephemeris,
+                )
+                    .chain()
+                    .run_if(live),
+                update_pansoft,
+            ),
+        )
         .add_systems(
             Update,
             (
                 draw_gizmos,
                 update_labels,
+                update_body_tree,
+                update_rotation,
                 // scroll_camera,
                 switch_focus_body,
                 look_at_target,
+                update_exposure,
+                toggle_shadows,
+                update_event_panel,
+                navigate_events,
+                (update_lagrange_points, draw_lagrange_points).chain(),
+                control_lagrange_points,
+                draw_influence_spheres,
+                toggle_influence_overlay,
+                (predict_maneuver, draw_maneuver, update_maneuver_panel).chain(),
+                edit_maneuver.before(PanOrbitCameraSystemSet),
+                (
+                    plot_porkchop,
+                    calculate_transfers,
+                    (control_propagation, update_propagation_panel).chain(),
+                    (show_reference_orbits, draw_reference_orbits).chain(),
+                    (cycle_orbit_display, draw_osculating_orbits).chain(),
+                    measure_perihelion_advance,
+                    cycle_zonal_harmonics,
+                    update_force_panel,
+                    draw_comet_tails,
+                    (quick_save, restore_snapshot).chain(),
+                    (
+                        control_recording,
+                        control_replay,
+                        play_replay,
+                        update_replay_panel,
+                    )
+                        .chain(),
+                    (control_export, update_export_panel).chain(),
+                ),
             ),
         )
         .run();
@@ -0,0 +1,371 @@
+use std::{error::Error, fs, io};
+
+use bevy::{math::DVec3, prelude::*, window::PrimaryWindow};
+use bevy_panorbit_camera::PanOrbitCamera;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::*,
+    format_time,
+    prediction::Ephemeris,
+    spacecraft::{local_frame, Burn, Spacecraft},
+    Coord, Mass, Ordinal, Primary, SimClock, Velocity,
+};
+
+/// Planned burn being edited, with its velocity change split into prograde,
+/// normal and radial components in m/s.
+#[derive(Clone, Copy, Debug)]
+pub struct ManeuverNode {
+    pub time: f64,
+    pub delta_v: DVec3,
+}
+
+impl ManeuverNode {
+    pub fn burn(&self) -> Burn {
+        Burn {
+            time: self.time,
+            direction: self.delta_v.normalize_or_zero(),
+            delta_v: self.delta_v.length(),
+        }
+    }
+}
+
+/// Burns of every spacecraft by name, saved to `maneuvers.ron` whenever a
+/// node is added and given back to the spacecraft of the same name when they
+/// are spawned.
+#[derive(Serialize, Deserialize, Default)]
+pub struct ManeuverPlan(Vec<(String, Vec<Burn>)>);
+
+const PLAN_FILE: &str = "maneuvers.ron";
+
+impl ManeuverPlan {
+    fn read(path: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(ron::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let config = PrettyConfig::default().compact_arrays(true);
+        fs::write(path, ron::ser::to_string_pretty(self, config)?)?;
+        Ok(())
+    }
+
+    /// The saved plan, empty when none was saved yet.
+    pub fn load() -> Self {
+        match ManeuverPlan::read(PLAN_FILE) {
+            Ok(plan) => plan,
+            Err(err)
+                if err
+                    .downcast_ref::<io::Error>()
+                    .is_some_and(|err| err.kind() == io::ErrorKind::NotFound) =>
+            {
+                ManeuverPlan::default()
+            }
+            Err(err) => {
+                warn!("Could not load {}: {}", PLAN_FILE, err);
+                ManeuverPlan::default()
+            }
+        }
+    }
+
+    pub fn burns(&self, name: &str) -> Option<Vec<Burn>> {
+        self.0
+            .iter()
+            .find(|(craft, _)| craft == name)
+            .map(|(_, burns)| burns.clone())
+    }
+}
+
+/// Predicted path of the focused spacecraft and the node being edited on it.
+///
+/// Clicking on the path places the node, dragging one of its handles changes
+/// the velocity change along that direction, `Enter` adds it to the
+/// spacecraft's burns and saves them and `Delete` discards it.
+#[derive(Resource, Default)]
+pub struct ManeuverEditor {
+    pub craft: Option<Entity>,
+    pub node: Option<ManeuverNode>,
+    /// Local direction of the handle being dragged
+    dragging: Option<DVec3>,
+    last_cursor: Option<Vec2>,
+    /// Where the craft's primary is now; the path is drawn around it so
+    /// orbits around a moving body close
+    origin: DVec3,
+    /// Time, position and velocity relative to the primary along the path
+    /// with the saved burns only
+    pub path: Vec<(f64, DVec3, DVec3)>,
+    /// Same, with the node performed as well
+    pub planned: Vec<(f64, DVec3, DVec3)>,
+}
+
+#[derive(Component)]
+pub struct ManeuverPanel;
+
+const HANDLES: [(DVec3, Color); 6] = [
+    (DVec3::X, Color::YELLOW_GREEN),
+    (DVec3::NEG_X, Color::YELLOW_GREEN),
+    (DVec3::Y, Color::VIOLET),
+    (DVec3::NEG_Y, Color::VIOLET),
+    (DVec3::Z, Color::CYAN),
+    (DVec3::NEG_Z, Color::CYAN),
+];
+
+/// Cursor distance, in pixels, at which the path or a handle is picked.
+const PICK_DISTANCE: f32 = 12.0;
+
+/// Length of the node handles relative to the camera distance.
+const HANDLE_LENGTH: f32 = 0.08;
+
+/// Velocity change per pixel the cursor is dragged along a handle, m/s.
+const DELTA_V_PER_PIXEL: f64 = 2.0;
+
+pub fn spawn_maneuver_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        ManeuverPanel,
+    ));
+}
+
+pub fn predict_maneuver(
+    clock: Res<SimClock>,
+    ephemeris: Res<Ephemeris>,
+    mut editor: ResMut<ManeuverEditor>,
+    crafts: Query<(&Spacecraft, &Coord, &Velocity, &Primary)>,
+    focusable: Query<Entity, With<Mass>>,
+    coords: Query<&Coord>,
+    camera: Query<&Ordinal>,
+) {
+    let focus = focusable.iter().nth(camera.single().0);
+    if editor.craft != focus {
+        *editor = ManeuverEditor {
+            craft: focus,
+            ..default()
+        };
+    }
+    editor.path.clear();
+    editor.planned.clear();
+
+    let Some(craft) = editor.craft else {
+        return;
+    };
+    let Ok((spacecraft, coord, velocity, primary)) = crafts.get(craft) else {
+        return;
+    };
+    let (Some(index), Ok(primary_coord)) = (ephemeris.index_of(primary.0), coords.get(primary.0))
+    else {
+        return;
+    };
+
+    let relative = |path: Vec<(f64, DVec3, DVec3)>| {
+        path.into_iter()
+            .map(|(t, r, v)| {
+                let (primary_r, primary_v) = ephemeris.state(index, t);
+                (t, r - primary_r, v - primary_v)
+            })
+            .collect()
+    };
+    let predict = |burns: &[Burn]| ephemeris.predict(clock.0, coord.0, velocity.0, index, burns);
+
+    editor.origin = primary_coord.0;
+    editor.path = relative(predict(&spacecraft.burns));
+    if let Some(node) = editor.node {
+        let mut burns = spacecraft.burns.clone();
+        burns.push(node.burn());
+        editor.planned = relative(predict(&burns));
+    }
+}
+
+impl ManeuverEditor {
+    /// Scene position of a point of the path.
+    fn scene(&self, position: DVec3) -> Vec3 {
+        ((self.origin + position) * SCALE).as_vec3()
+    }
+
+    /// Scene position and local frame of the node.
+    fn node_frame(&self) -> Option<(Vec3, [Vec3; 3])> {
+        let node = self.node?;
+        let &(_, position, velocity) = self.path.iter().find(|sample| sample.0 >= node.time)?;
+        let (prograde, normal, radial) = local_frame(position, velocity, DVec3::ZERO, DVec3::ZERO);
+        Some((
+            self.scene(position),
+            [prograde.as_vec3(), normal.as_vec3(), radial.as_vec3()],
+        ))
+    }
+}
+
+fn handle_direction(frame: &[Vec3; 3], local: DVec3) -> Vec3 {
+    frame[0] * local.x as f32 + frame[1] * local.y as f32 + frame[2] * local.z as f32
+}
+
+pub fn draw_maneuver(
+    mut gizmos: Gizmos,
+    editor: Res<ManeuverEditor>,
+    camera: Query<&Transform, With<Camera>>,
+) {
+    let color = if editor.node.is_some() {
+        Color::DARK_GRAY
+    } else {
+        Color::ORANGE
+    };
+    for pair in editor.path.windows(2) {
+        gizmos.line(editor.scene(pair[0].1), editor.scene(pair[1].1), color);
+    }
+    for pair in editor.planned.windows(2) {
+        gizmos.line(
+            editor.scene(pair[0].1),
+            editor.scene(pair[1].1),
+            Color::ORANGE,
+        );
+    }
+
+    let Some((position, frame)) = editor.node_frame() else {
+        return;
+    };
+    let camera_transform = camera.single();
+    let length = (camera_transform.translation - position).length() * HANDLE_LENGTH;
+    let normal = camera_transform.rotation * Vec3::Z;
+    gizmos.circle(position, normal, length * 0.2, Color::ORANGE);
+    for (local, color) in HANDLES {
+        let tip = position + handle_direction(&frame, local) * length;
+        gizmos.line(position, tip, color);
+        gizmos.circle(tip, normal, length * 0.1, color);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn edit_maneuver(
+    mouse_input: Res<Input<MouseButton>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut editor: ResMut<ManeuverEditor>,
+    mut crafts: Query<(&Name, &mut Spacecraft)>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut camera: Query<(&Camera, &GlobalTransform, &mut PanOrbitCamera)>,
+) {
+    let (camera, camera_transform, mut pan_orbit) = camera.single_mut();
+    if mouse_input.just_released(MouseButton::Left) {
+        editor.dragging = None;
+        pan_orbit.enabled = true;
+    }
+    let Some(craft) = editor.craft else {
+        return;
+    };
+    let Ok((_, mut spacecraft)) = crafts.get_mut(craft) else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Delete) || keyboard_input.just_pressed(KeyCode::Back) {
+        editor.node = None;
+    }
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        if let Some(node) = editor.node.take() {
+            if node.delta_v != DVec3::ZERO {
+                spacecraft.burns.push(node.burn());
+                let plan = ManeuverPlan(
+                    crafts
+                        .iter()
+                        .map(|(name, craft)| (name.to_string(), craft.burns.clone()))
+                        .collect(),
+                );
+                if let Err(err) = plan.write(PLAN_FILE) {
+                    warn!("Could not write {}: {}", PLAN_FILE, err);
+                }
+            }
+        }
+    }
+
+    let cursor = window.single().cursor_position();
+    let to_screen = |position: Vec3| camera.world_to_viewport(camera_transform, position);
+    let frame = editor.node_frame();
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        if let Some(cursor) = cursor {
+            let handle = frame.and_then(|(position, frame)| {
+                let length = (camera_transform.translation() - position).length() * HANDLE_LENGTH;
+                HANDLES.iter().map(|handle| handle.0).find(|&local| {
+                    to_screen(position + handle_direction(&frame, local) * length)
+                        .is_some_and(|tip| tip.distance(cursor) < PICK_DISTANCE)
+                })
+            });
+            if handle.is_some() {
+                editor.dragging = handle;
+                pan_orbit.enabled = false;
+            } else if let Some(&(time, ..)) = editor.path.iter().find(|sample| {
+                to_screen(editor.scene(sample.1))
+                    .is_some_and(|point| point.distance(cursor) < PICK_DISTANCE)
+            }) {
+                let delta_v = editor.node.map_or(DVec3::ZERO, |node| node.delta_v);
+                editor.node = Some(ManeuverNode { time, delta_v });
+                pan_orbit.enabled = false;
+            }
+        }
+    }
+
+    if let (Some(local), Some((position, frame)), Some(cursor), Some(last)) =
+        (editor.dragging, frame, cursor, editor.last_cursor)
+    {
+        let length = (camera_transform.translation() - position).length() * HANDLE_LENGTH;
+        if let (Some(base), Some(tip)) = (
+            to_screen(position),
+            to_screen(position + handle_direction(&frame, local) * length),
+        ) {
+            let along = (cursor - last).dot((tip - base).normalize_or_zero());
+            if let Some(node) = editor.node.as_mut() {
+                node.delta_v += local * along as f64 * DELTA_V_PER_PIXEL;
+            }
+        }
+    }
+    if cursor.is_some() {
+        editor.last_cursor = cursor;
+    }
+}
+
+pub fn update_maneuver_panel(
+    editor: Res<ManeuverEditor>,
+    crafts: Query<(&Name, &Spacecraft)>,
+    mut panel: Query<&mut Text, With<ManeuverPanel>>,
+) {
+    let mut text = panel.single_mut();
+    let Some((name, spacecraft)) = editor.craft.and_then(|craft| crafts.get(craft).ok()) else {
+        text.sections[0].value.clear();
+        return;
+    };
+
+    let mut lines = vec![format!(
+        "{}: {:.0} kg propellant, {:.0} m/s left",
+        name,
+        spacecraft.propellant,
+        spacecraft.delta_v_budget()
+    )];
+    for burn in &spacecraft.burns {
+        lines.push(format!(
+            " {} burn {:.1} m/s",
+            format_time(burn.time),
+            burn.delta_v
+        ));
+    }
+    match editor.node {
+        Some(node) => lines.push(format!(
+            ">{} node {:.1} m/s: prograde {:.1}, normal {:.1}, radial {:.1}",
+            format_time(node.time),
+            node.delta_v.length(),
+            node.delta_v.x,
+            node.delta_v.y,
+            node.delta_v.z
+        )),
+        None => lines.push("Click on the predicted path to add a node".to_string()),
+    }
+    text.sections[0].value = lines.join("\n");
+}
@@ -0,0 +1,159 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{constants::*, influence::hill_radius, Coord, Mass, Primary, Spin, Velocity};
+
+/// Equatorial radius in meters and zonal harmonic coefficients J2, J3 and J4
+/// of the bodies whose flattening matters to what orbits them.
+const ZONAL_HARMONICS: &[(&str, f64, [f64; 3])] = &[
+    ("Sun", 6.957e8, [2.2e-7, 0.0, 0.0]),
+    ("Earth", 6.378137e6, [1.08263e-3, -2.5327e-6, -1.6196e-6]),
+    ("Moon", 1.738e6, [2.0330e-4, 8.476e-6, -9.592e-6]),
+    ("Mars", 3.3962e6, [1.96045e-3, 3.15e-5, -1.54e-5]),
+    ("Jupiter", 7.1492e7, [1.4736e-2, -4.2e-8, -5.87e-4]),
+    ("Saturn", 6.0268e7, [1.6298e-2, 1.5e-7, -9.15e-4]),
+    ("Uranus", 2.5559e7, [3.343e-3, 0.0, -2.9e-5]),
+    ("Neptune", 2.4764e7, [3.411e-3, 0.0, -3.5e-5]),
+];
+
+/// Departure of a body's gravity field from a point mass, symmetric about
+/// its rotation axis.
+#[derive(Component)]
+pub struct Oblateness {
+    radius: f64,
+    /// J2, J3 and J4
+    zonal: [f64; 3],
+}
+
+/// Highest degree of the zonal harmonics applied, 0 when bodies attract as
+/// point masses; `Z` cycles between none, J2 alone and J2 to J4.
+#[derive(Resource, Default)]
+pub struct ZonalHarmonics {
+    pub degree: usize,
+}
+
+pub fn oblate(harmonics: Res<ZonalHarmonics>) -> bool {
+    harmonics.degree >= 2
+}
+
+pub fn spawn_oblateness(mut commands: Commands, bodies: Query<(Entity, &Name), With<Spin>>) {
+    for (entity, name) in &bodies {
+        if let Some(&(_, radius, zonal)) = ZONAL_HARMONICS
+            .iter()
+            .find(|harmonics| harmonics.0 == name.as_str())
+        {
+            commands.entity(entity).insert(Oblateness { radius, zonal });
+        }
+    }
+}
+
+pub fn cycle_zonal_harmonics(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut harmonics: ResMut<ZonalHarmonics>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Z) {
+        harmonics.degree = match harmonics.degree {
+            0 => 2,
+            2 => 4,
+            _ => 0,
+        };
+        match harmonics.degree {
+            0 => info!("Zonal harmonics off"),
+            degree => info!("Zonal harmonics up to J{}", degree),
+        }
+    }
+}
+
+/// Legendre polynomial of degree `n` and its derivative.
+fn legendre(n: usize, u: f64) -> (f64, f64) {
+    let (mut p, mut previous) = (u, 1.0);
+    let (mut dp, mut previous_dp) = (1.0, 0.0);
+    for k in 1..n {
+        let k = k as f64;
+        let next = ((2.0 * k + 1.0) * u * p - k * previous) / (k + 1.0);
+        let next_dp = previous_dp + (2.0 * k + 1.0) * p;
+        (previous, p) = (p, next);
+        (previous_dp, dp) = (dp, next_dp);
+    }
+    (p, dp)
+}
+
+/// Acceleration, beyond the point-mass attraction, at `r` from the center of
+/// a body with gravitational parameter `mu` and rotation axis `pole`, from its
+/// zonal harmonics up to `degree`.
+pub fn zonal_acceleration(
+    r: DVec3,
+    mu: f64,
+    pole: DVec3,
+    oblateness: &Oblateness,
+    degree: usize,
+) -> DVec3 {
+    let distance = r.length();
+    let direction = r / distance;
+    let u = direction.dot(pole);
+    (2..=degree.min(4))
+        .map(|n| {
+            let (p, dp) = legendre(n, u);
+            let scale =
+                mu * oblateness.zonal[n - 2] * (oblateness.radius / distance).powi(n as i32)
+                    / distance.powi(2);
+            (direction * ((n + 1) as f64 * p + u * dp) - pole * dp) * scale
+        })
+        .sum()
+}
+
+/// Adds the pull of the flattening of each oblate body to the bodies within
+/// its Hill sphere, and the reaction to the oblate body itself.
+pub fn zonal_attraction(
+    harmonics: Res<ZonalHarmonics>,
+    oblate: Query<(Entity, &Oblateness, &Spin, &Mass, Option<&Primary>)>,
+    mut bodies: Query<(Entity, &Mass, &Coord, &mut Velocity)>,
+) {
+    let dt = DT as f64;
+    for (entity, oblateness, spin, mass, primary) in &oblate {
+        let Ok((_, _, &Coord(center), _)) = bodies.get(entity) else {
+            continue;
+        };
+        let reach = match primary.and_then(|primary| bodies.get(primary.0).ok()) {
+            Some((_, primary_mass, primary_coord, _)) => {
+                hill_radius(mass.0, primary_mass.0, center.distance(primary_coord.0))
+            }
+            None => f64::INFINITY,
+        };
+        let pole = (spin.pole * Vec3::Z).as_dvec3();
+        let mu = G * mass.0;
+
+        let mut reaction = DVec3::ZERO;
+        for (other, other_mass, coord, mut velocity) in &mut bodies {
+            let r = coord.0 - center;
+            if other == entity || r.length() > reach {
+                continue;
+            }
+            let acceleration = zonal_acceleration(r, mu, pole, oblateness, harmonics.degree);
+            velocity.0 += acceleration * dt;
+            reaction -= acceleration * (other_mass.0 / mass.0);
+        }
+        if let Ok((.., mut velocity)) = bodies.get_mut(entity) {
+            velocity.0 += reaction * dt;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{setup, tests::headless_app};
+
+    #[test]
+    fn startup_flattens_the_earth() {
+        let mut app = headless_app();
+        app.add_systems(Startup, (setup, apply_deferred, spawn_oblateness).chain());
+        app.update();
+        let world = &mut app.world;
+        let earth = world
+            .query::<(&Name, Has<Oblateness>)>()
+            .iter(world)
+            .find(|(name, _)| name.as_str() == "Earth")
+            .map(|(_, oblate)| oblate);
+        assert_eq!(earth, Some(true));
+    }
+}
@@ -0,0 +1,98 @@
+use std::f64::consts::PI;
+
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{constants::*, kepler::Elements, Coord, Mass, Primary, Velocity};
+
+/// Points drawn on each osculating conic.
+const SEGMENTS: usize = 128;
+/// Below this eccentricity an orbit has no meaningful apsides.
+const CIRCULAR: f64 = 1e-3;
+
+/// What `draw_gizmos` and `draw_osculating_orbits` show of each orbit; `V`
+/// cycles through the modes.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OrbitDisplay {
+    /// The path recorded so far
+    #[default]
+    Trails,
+    /// The conic each body would follow from its current state if only its
+    /// primary attracted it
+    Conics,
+    Both,
+}
+
+impl OrbitDisplay {
+    pub fn trails(self) -> bool {
+        self != OrbitDisplay::Conics
+    }
+
+    pub fn conics(self) -> bool {
+        self != OrbitDisplay::Trails
+    }
+}
+
+pub fn cycle_orbit_display(keyboard_input: Res<Input<KeyCode>>, mut display: ResMut<OrbitDisplay>) {
+    if keyboard_input.just_pressed(KeyCode::V) {
+        *display = match *display {
+            OrbitDisplay::Trails => OrbitDisplay::Conics,
+            OrbitDisplay::Conics => OrbitDisplay::Both,
+            OrbitDisplay::Both => OrbitDisplay::Trails,
+        };
+    }
+}
+
+/// Draws the osculating conic of every body around its primary, with its
+/// periapsis (yellow) and apoapsis (cyan), and where it crosses the ecliptic
+/// going north (lime) and south (maroon).
+pub fn draw_osculating_orbits(
+    mut gizmos: Gizmos,
+    display: Res<OrbitDisplay>,
+    bodies: Query<(&Mass, &Coord, &Velocity, &Primary)>,
+    primaries: Query<(&Mass, &Coord, &Velocity)>,
+    camera: Query<&Transform, With<Camera>>,
+) {
+    if !display.conics() {
+        return;
+    }
+    let camera = camera.single();
+    let normal = camera.rotation * Vec3::Z;
+
+    for (mass, coord, velocity, primary) in &bodies {
+        let Ok((primary_mass, primary_coord, primary_velocity)) = primaries.get(primary.0) else {
+            continue;
+        };
+        let r = coord.0 - primary_coord.0;
+        let v = velocity.0 - primary_velocity.0;
+        let elements = Elements::from_state(r, v, G * (primary_mass.0 + mass.0));
+        let center = primary_coord.0;
+        let scene = |point: DVec3| ((center + point) * SCALE).as_vec3();
+
+        let points = elements.points(SEGMENTS, 2.0 * r.length());
+        gizmos.linestrip(points.into_iter().map(scene), Color::rgb(0.2, 0.3, 0.5));
+
+        let mut marker = |point: DVec3, color: Color| {
+            let position = scene(point);
+            let radius = camera.translation.distance(position) * 0.006;
+            gizmos.circle(position, normal, radius, color);
+        };
+        if elements.eccentricity > CIRCULAR {
+            marker(
+                elements.periapsis * elements.periapsis_distance(),
+                Color::YELLOW,
+            );
+            if let Some(apoapsis) = elements.apoapsis_distance() {
+                marker(-elements.periapsis * apoapsis, Color::CYAN);
+            }
+        }
+        if let Some(node) = elements.ascending_node {
+            let anomaly = elements.true_anomaly(node);
+            if let Some(point) = elements.position(anomaly) {
+                marker(point, Color::LIME_GREEN);
+            }
+            if let Some(point) = elements.position(anomaly + PI) {
+                marker(point, Color::MAROON);
+            }
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use bevy::{math::DVec3, prelude::*, utils::HashMap};
+
+use crate::{
+    constants::*, influence::sphere_of_influence, kepler::propagate, spacecraft::Spacecraft, Coord,
+    Kind, Mass, Primary, SimClock, Velocity,
+};
+
+/// How bodies move: full N-body integration, or Kepler orbits around the
+/// body whose sphere of influence they are in. `M` switches between the two,
+/// `[` and `]` divide and multiply the time warp by ten.
+#[derive(Resource)]
+pub struct Propagation {
+    pub patched_conics: bool,
+    /// Timesteps simulated per fixed step
+    pub warp: u32,
+}
+
+impl Propagation {
+    /// N-body steps cost a pass over every pair of bodies per substep, so
+    /// they are warped less than Kepler orbits.
+    pub fn max_warp(&self) -> u32 {
+        if self.patched_conics {
+            MAX_WARP
+        } else {
+            MAX_N_BODY_WARP
+        }
+    }
+}
+
+impl Default for Propagation {
+    fn default() -> Self {
+        Propagation {
+            patched_conics: false,
+            warp: 1,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PropagationPanel;
+
+pub fn n_body(propagation: Res<Propagation>) -> bool {
+    !propagation.patched_conics
+}
+
+pub fn patched_conics(propagation: Res<Propagation>) -> bool {
+    propagation.patched_conics
+}
+
+/// Natural body as it is at the start of a step.
+struct Natural {
+    mass: f64,
+    coord: DVec3,
+    velocity: DVec3,
+    primary: Option<Entity>,
+}
+
+/// Orbits of the natural bodies, each around its primary, from their states
+/// at the start of the step.
+struct Conics(HashMap<Entity, Natural>);
+
+impl Conics {
+    /// Absolute state of a body `dt` seconds into the step.
+    fn state(&self, entity: Entity, dt: f64) -> (DVec3, DVec3) {
+        let body = &self.0[&entity];
+        let Some(primary) = body.primary.filter(|p| self.0.contains_key(p)) else {
+            return (body.coord + body.velocity * dt, body.velocity);
+        };
+        let parent = &self.0[&primary];
+        let (r, v) = propagate(
+            body.coord - parent.coord,
+            body.velocity - parent.velocity,
+            G * (parent.mass + body.mass),
+            dt,
+        );
+        let (parent_r, parent_v) = self.state(primary, dt);
+        (parent_r + r, parent_v + v)
+    }
+
+    /// Radius of the sphere of influence of a body, `dt` seconds into the step.
+    fn sphere_of_influence(&self, entity: Entity, dt: f64) -> f64 {
+        let body = &self.0[&entity];
+        match body.primary.filter(|p| self.0.contains_key(p)) {
+            Some(primary) => {
+                let distance = self.state(entity, dt).0.distance(self.state(primary, dt).0);
+                sphere_of_influence(body.mass, self.0[&primary].mass, distance)
+            }
+            None => f64::INFINITY,
+        }
+    }
+
+    /// Body whose sphere of influence a point is in, looking one level up and
+    /// down from its current one.
+    fn parent(&self, current: Entity, point: DVec3, dt: f64) -> Entity {
+        let children = self
+            .0
+            .iter()
+            .filter(|(_, body)| body.primary == Some(current))
+            .map(|(entity, _)| *entity);
+        for child in children {
+            if self.state(child, dt).0.distance(point) < self.sphere_of_influence(child, dt) {
+                return child;
+            }
+        }
+        match self.0[&current].primary {
+            Some(primary)
+                if self.state(current, dt).0.distance(point)
+                    > self.sphere_of_influence(current, dt) =>
+            {
+                primary
+            }
+            _ => current,
+        }
+    }
+}
+
+type Body<'a> = (
+    Entity,
+    &'a Name,
+    &'a mut Mass,
+    &'a mut Coord,
+    &'a mut Velocity,
+    &'a mut Transform,
+    &'a Kind,
+    Option<&'a mut Primary>,
+    Option<&'a mut Spacecraft>,
+);
+
+/// Moves bodies one `TIMESTEP` along Kepler orbits around their primary.
+/// Spacecraft and test particles switch to the body whose sphere of influence
+/// they enter at the end of the step and perform their burns on the way.
+pub fn propagate_conics(mut clock: ResMut<SimClock>, mut bodies: Query<Body>) {
+    let natural = |kind: &Kind| !matches!(kind, Kind::Spacecraft | Kind::Particle);
+    let conics = Conics(
+        bodies
+            .iter()
+            .filter(|body| natural(body.6))
+            .map(|body| {
+                let natural = Natural {
+                    mass: body.2 .0,
+                    coord: body.3 .0,
+                    velocity: body.4 .0,
+                    primary: body.7.map(|p| p.0),
+                };
+                (body.0, natural)
+            })
+            .collect(),
+    );
+    let duration = TIMESTEP as f64;
+    let start = clock.0;
+
+    for (entity, name, mut mass, mut coord, mut velocity, mut transform, kind, primary, craft) in
+        &mut bodies
+    {
+        let (r, v) = if natural(kind) {
+            conics.state(entity, duration)
+        } else {
+            let Some(mut primary) = primary.filter(|p| conics.0.contains_key(&p.0)) else {
+                continue;
+            };
+            let mut craft = craft;
+            let (parent_r, parent_v) = conics.state(primary.0, 0.0);
+            let mu = G * (conics.0[&primary.0].mass + mass.0);
+            let (mut rel_r, mut rel_v) = (coord.0 - parent_r, velocity.0 - parent_v);
+            let mut t = 0.0;
+
+            // Burns split the step so they happen at their exact time
+            if let Some(craft) = craft.as_mut() {
+                let mut burns: Vec<_> = craft
+                    .burns
+                    .iter()
+                    .filter(|burn| (start..start + duration).contains(&burn.time))
+                    .copied()
+                    .collect();
+                burns.sort_by(|a, b| a.time.total_cmp(&b.time));
+                for burn in burns {
+                    (rel_r, rel_v) = propagate(rel_r, rel_v, mu, burn.time - start - t);
+                    t = burn.time - start;
+                    rel_v += craft.execute(name, &burn, rel_r, rel_v);
+                    mass.0 = craft.dry_mass + craft.propellant;
+                }
+            }
+            (rel_r, rel_v) = propagate(rel_r, rel_v, mu, duration - t);
+
+            let (parent_r, parent_v) = conics.state(primary.0, duration);
+            let (r, v) = (parent_r + rel_r, parent_v + rel_v);
+            primary.0 = conics.parent(primary.0, r, duration);
+            (r, v)
+        };
+
+        coord.0 = r;
+        velocity.0 = v;
+        transform.translation = (r * SCALE).as_vec3();
+    }
+
+    clock.0 += duration;
+}
+
+pub fn control_propagation(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut propagation: ResMut<Propagation>,
+) {
+    if keyboard_input.just_pressed(KeyCode::M) {
+        propagation.patched_conics = !propagation.patched_conics;
+        propagation.warp = propagation.warp.min(propagation.max_warp());
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketRight) {
+        propagation.warp = (propagation.warp * 10).min(propagation.max_warp());
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketLeft) {
+        propagation.warp = (propagation.warp / 10).max(1);
+    }
+}
+
+pub fn spawn_propagation_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+        PropagationPanel,
+    ));
+}
+
+pub fn update_propagation_panel(
+    propagation: Res<Propagation>,
+    mut panel: Query<&mut Text, With<PropagationPanel>>,
+) {
+    let mode = if propagation.patched_conics {
+        "Patched conics"
+    } else {
+        "N-body"
+    };
+    panel.single_mut().sections[0].value = format!("{} x{}", mode, propagation.warp);
+}
@@ -0,0 +1,380 @@
+use std::{f64::consts::PI, fmt::Write as _, fs};
+
+use bevy::{math::DVec3, prelude::*, render::render_resource::TextureFormat};
+
+use crate::{
+    constants::*, format_time, lambert::lambert, prediction::Ephemeris, textures::image, Coord,
+    Kind, Mass, Ordinal, Primary, SimClock, Velocity,
+};
+
+pub const PORKCHOP_PNG: &str = "porkchop.png";
+pub const PORKCHOP_CSV: &str = "porkchop.csv";
+
+/// Departure and arrival dates sampled along each axis of the plot.
+const RESOLUTION: usize = 128;
+
+/// `B` makes the focused body the origin of transfers, the Earth when `None`;
+/// `P` plots transfers from the origin to the focused body.
+#[derive(Resource, Default)]
+pub struct Porkchop {
+    pub origin: Option<Entity>,
+    pub visible: bool,
+}
+
+impl Porkchop {
+    pub(crate) fn origin(&self, bodies: &[Body]) -> Option<Entity> {
+        self.origin.or_else(|| {
+            bodies
+                .iter()
+                .find(|body| body.1.as_str() == "Earth")
+                .map(|body| body.0)
+        })
+    }
+}
+
+#[derive(Component)]
+pub struct PorkchopPanel;
+
+#[derive(Component)]
+pub struct PorkchopPlot;
+
+#[derive(Component)]
+pub struct PorkchopCaption;
+
+pub(crate) type Body<'a> = (
+    Entity,
+    &'a Name,
+    &'a Mass,
+    &'a Coord,
+    &'a Velocity,
+    &'a Kind,
+    Option<&'a Primary>,
+);
+
+/// Transfers between two bodies orbiting the same central body, sampled on
+/// a grid of departure and arrival times.
+struct Sweep {
+    departures: Vec<f64>,
+    arrivals: Vec<f64>,
+    /// Departure and arrival delta-v, by arrival then departure
+    delta_v: Vec<Option<(f64, f64)>>,
+}
+
+impl Sweep {
+    fn best(&self) -> Option<(usize, f64)> {
+        self.delta_v
+            .iter()
+            .enumerate()
+            .filter_map(|(i, dv)| dv.map(|(departure, arrival)| (i, departure + arrival)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Heatmap with departure to the right and arrival upward, from blue at
+    /// the cheapest transfer to red at three times its delta-v.
+    fn image(&self) -> Image {
+        let minimum = self.best().map_or(0.0, |best| best.1);
+        let (width, height) = (self.departures.len(), self.arrivals.len());
+        let mut data = Vec::with_capacity(width * height * 4);
+        for row in (0..height).rev() {
+            for dv in &self.delta_v[row * width..(row + 1) * width] {
+                let color = match dv {
+                    Some((departure, arrival)) => {
+                        let t = ((departure + arrival) / minimum - 1.0) / 2.0;
+                        Color::hsl(240.0 * (1.0 - t.clamp(0.0, 1.0)) as f32, 1.0, 0.5)
+                    }
+                    None => Color::BLACK,
+                };
+                data.extend(color.as_rgba_u8());
+            }
+        }
+        image(width, height, data, TextureFormat::Rgba8UnormSrgb)
+    }
+
+    fn csv(&self) -> String {
+        let mut csv =
+            "departure_s,arrival_s,departure_dv_m_s,arrival_dv_m_s,total_dv_m_s\n".to_string();
+        for (row, arrival) in self.arrivals.iter().enumerate() {
+            for (column, departure) in self.departures.iter().enumerate() {
+                if let Some((dv1, dv2)) = self.delta_v[row * self.departures.len() + column] {
+                    let _ = writeln!(
+                        csv,
+                        "{:.0},{:.0},{:.3},{:.3},{:.3}",
+                        departure,
+                        arrival,
+                        dv1,
+                        dv2,
+                        dv1 + dv2
+                    );
+                }
+            }
+        }
+        csv
+    }
+}
+
+pub fn spawn_porkchop_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(10.0),
+                    right: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            PorkchopPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ImageBundle {
+                    style: Style {
+                        width: Val::Px(256.0),
+                        height: Val::Px(256.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                PorkchopPlot,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("PressStart2P-Regular.ttf"),
+                        font_size: 10.0,
+                        color: Color::ANTIQUE_WHITE,
+                    },
+                ),
+                PorkchopCaption,
+            ));
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn plot_porkchop(
+    keyboard_input: Res<Input<KeyCode>>,
+    clock: Res<SimClock>,
+    mut porkchop: ResMut<Porkchop>,
+    mut images: ResMut<Assets<Image>>,
+    bodies: Query<Body>,
+    focusable: Query<Entity, With<Mass>>,
+    camera: Query<&Ordinal>,
+    mut panel: Query<&mut Visibility, With<PorkchopPanel>>,
+    mut plot: Query<&mut UiImage, With<PorkchopPlot>>,
+    mut caption: Query<&mut Text, With<PorkchopCaption>>,
+) {
+    let focus = focusable.iter().nth(camera.single().0);
+    if keyboard_input.just_pressed(KeyCode::B) {
+        porkchop.origin = focus;
+    }
+    if !keyboard_input.just_pressed(KeyCode::P) {
+        return;
+    }
+    porkchop.visible = !porkchop.visible;
+    *panel.single_mut() = if porkchop.visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if !porkchop.visible {
+        return;
+    }
+
+    let bodies: Vec<_> = bodies
+        .iter()
+        .filter(|body| !matches!(body.5, Kind::Spacecraft | Kind::Particle))
+        .collect();
+    let origin = porkchop.origin(&bodies);
+    let text = &mut caption.single_mut().sections[0].value;
+    let (Some(origin), Some(target)) = (origin, focus) else {
+        *text = "Focus a body to plot transfers to".to_string();
+        return;
+    };
+
+    match sweep(&bodies, origin, target, clock.0) {
+        Ok((sweep, title)) => {
+            *text = summary(&sweep, &title);
+            let plot_image = sweep.image();
+            if let Err(err) = plot_image
+                .clone()
+                .try_into_dynamic()
+                .map_err(|err| err.to_string())
+                .and_then(|dynamic| dynamic.save(PORKCHOP_PNG).map_err(|err| err.to_string()))
+            {
+                warn!("Could not write {}: {}", PORKCHOP_PNG, err);
+            }
+            if let Err(err) = fs::write(PORKCHOP_CSV, sweep.csv()) {
+                warn!("Could not write {}: {}", PORKCHOP_CSV, err);
+            }
+            plot.single_mut().texture = images.add(plot_image);
+        }
+        Err(message) => *text = message,
+    }
+}
+
+fn summary(sweep: &Sweep, title: &str) -> String {
+    let Some((i, total)) = sweep.best() else {
+        return format!("{}: no transfer found", title);
+    };
+    let width = sweep.departures.len();
+    let (departure, arrival) = sweep.delta_v[i].unwrap();
+    let range = |times: &[f64]| {
+        format!(
+            "{} - {}",
+            format_time(times[0]),
+            format_time(times[times.len() - 1])
+        )
+    };
+    format!(
+        "{}\nDeparture (right): {}\nArrival (up): {}\nBest {:.2} km/s ({:.2} + {:.2})\nleaving {}, arriving {}",
+        title,
+        range(&sweep.departures),
+        range(&sweep.arrivals),
+        total / 1e3,
+        departure / 1e3,
+        arrival / 1e3,
+        format_time(sweep.departures[i % width]),
+        format_time(sweep.arrivals[i / width]),
+    )
+}
+
+/// Bodies the given one orbits, itself first and outward from there.
+fn ancestry(bodies: &[Body], entity: Entity) -> Vec<Entity> {
+    let mut chain = vec![entity];
+    while let Some(primary) = bodies
+        .iter()
+        .find(|body| body.0 == *chain.last().unwrap())
+        .and_then(|body| body.6)
+    {
+        chain.push(primary.0);
+    }
+    chain
+}
+
+/// Name of a transfer, the body both ends orbit and the bodies directly
+/// orbiting it that carry each end.
+pub(crate) fn transfer_ends(
+    bodies: &[Body],
+    origin: Entity,
+    target: Entity,
+) -> Result<(String, Entity, Entity, Entity), String> {
+    let name = |entity: Entity| {
+        bodies
+            .iter()
+            .find(|body| body.0 == entity)
+            .map_or("?".to_string(), |body| body.1.to_string())
+    };
+    let title = format!("{} -> {}", name(origin), name(target));
+    let origin_chain = ancestry(bodies, origin);
+    let target_chain = ancestry(bodies, target);
+    let Some(central) = origin_chain
+        .iter()
+        .copied()
+        .find(|entity| target_chain.contains(entity))
+    else {
+        return Err(format!("{}: no common primary", title));
+    };
+    if central == origin || central == target {
+        return Err(format!("{}: one orbits the other", title));
+    }
+    let child = |chain: &[Entity]| chain[chain.iter().position(|&e| e == central).unwrap() - 1];
+    Ok((title, central, child(&origin_chain), child(&target_chain)))
+}
+
+/// Integrates the system the two bodies share ahead of `now` and solves
+/// Lambert's problem for every pair of departure and arrival times. Moons are
+/// moved along with their planet, whose mass they add to.
+fn sweep(
+    bodies: &[Body],
+    origin: Entity,
+    target: Entity,
+    now: f64,
+) -> Result<(Sweep, String), String> {
+    let (title, central, origin, target) = transfer_ends(bodies, origin, target)?;
+
+    let mut system: Vec<_> = bodies
+        .iter()
+        .filter(|body| body.0 == central || body.6.is_some_and(|p| p.0 == central))
+        .map(|body| (body.0, body.2 .0, body.3 .0, body.4 .0))
+        .collect();
+    for body in bodies {
+        let chain = ancestry(bodies, body.0);
+        if let Some(i) = chain.iter().position(|&e| e == central).filter(|&i| i > 1) {
+            if let Some(carrier) = system.iter_mut().find(|s| s.0 == chain[i - 1]) {
+                carrier.1 += body.2 .0;
+            }
+        }
+    }
+    let index = |entity: Entity| system.iter().position(|s| s.0 == entity).unwrap();
+    let (c, o, t) = (index(central), index(origin), index(target));
+    let mu = G * system[c].1;
+
+    // Orbital periods from the current states set the ranges of the plot
+    let period = |i: usize| {
+        let (r, v) = (system[i].2 - system[c].2, system[i].3 - system[c].3);
+        let a = 1.0 / (2.0 / r.length() - v.length_squared() / mu);
+        (a > 0.0).then(|| 2.0 * PI * (a.powi(3) / mu).sqrt())
+    };
+    let (Some(origin_period), Some(target_period)) = (period(o), period(t)) else {
+        return Err(format!("{}: not on a closed orbit", title));
+    };
+    let shortest = (0..system.len())
+        .filter(|&i| i != c)
+        .filter_map(period)
+        .fold(f64::MAX, f64::min);
+    let distance = |i: usize| (system[i].2 - system[c].2).length();
+    let hohmann = PI * ((distance(o) + distance(t)).powi(3) / (8.0 * mu)).sqrt();
+    let synodic = 1.0 / (1.0 / origin_period - 1.0 / target_period).abs();
+    let span = synodic.min(2.0 * origin_period.max(target_period));
+    let (shortest_flight, longest_flight) = (0.4 * hohmann, 1.6 * hohmann);
+
+    let step = shortest / 200.0;
+    let steps = ((span + longest_flight) / step).ceil() as usize;
+    if steps > 1_000_000 {
+        return Err(format!("{}: transfer too long to integrate", title));
+    }
+    let ephemeris = Ephemeris::compute(now, &system, step, steps);
+
+    let samples = |from: f64, to: f64| {
+        (0..RESOLUTION)
+            .map(|i| from + (to - from) * i as f64 / (RESOLUTION - 1) as f64)
+            .collect::<Vec<_>>()
+    };
+    let departures = samples(now, now + span);
+    let arrivals = samples(now + shortest_flight, now + span + longest_flight);
+    let relative = |i: usize, time: f64| -> (DVec3, DVec3) {
+        let (r, v) = ephemeris.state(i, time);
+        let (rc, vc) = ephemeris.state(c, time);
+        (r - rc, v - vc)
+    };
+
+    let mut delta_v = Vec::with_capacity(RESOLUTION * RESOLUTION);
+    for &arrival in &arrivals {
+        for &departure in &departures {
+            let flight = arrival - departure;
+            if flight < shortest_flight * 0.5 {
+                delta_v.push(None);
+                continue;
+            }
+            let (r1, v_origin) = relative(o, departure);
+            let (r2, v_target) = relative(t, arrival);
+            delta_v.push(
+                lambert(r1, r2, flight, mu, true)
+                    .map(|(v1, v2)| ((v1 - v_origin).length(), (v_target - v2).length())),
+            );
+        }
+    }
+
+    Ok((
+        Sweep {
+            departures,
+            arrivals,
+            delta_v,
+        },
+        title,
+    ))
+}
@@ -0,0 +1,153 @@
+use bevy::{math::DVec3, prelude::*};
+
+use crate::{
+    constants::*,
+    events::ClockJumped,
+    spacecraft::{Burn, Spacecraft},
+    Coord, Mass, SimClock, Velocity,
+};
+
+/// Positions and velocities of the natural bodies sampled at a fixed step
+/// ahead of the clock, so spacecraft trajectories can be predicted without
+/// integrating the whole system again for each of them.
+#[derive(Resource, Default)]
+pub struct Ephemeris {
+    pub start: f64,
+    pub step: f64,
+    bodies: Vec<(Entity, f64)>,
+    /// States of every body, one row per step
+    states: Vec<Vec<(DVec3, DVec3)>>,
+}
+
+impl Ephemeris {
+    /// Integrates the given bodies (entity, mass, position and velocity) for
+    /// `steps` steps from `start`.
+    pub fn compute(
+        start: f64,
+        bodies: &[(Entity, f64, DVec3, DVec3)],
+        step: f64,
+        steps: usize,
+    ) -> Self {
+        let masses: Vec<_> = bodies.iter().map(|body| body.1).collect();
+        let mut row: Vec<_> = bodies.iter().map(|body| (body.2, body.3)).collect();
+        let mut states = Vec::with_capacity(steps + 1);
+        states.push(row.clone());
+
+        for _ in 0..steps {
+            for i in 0..row.len() {
+                for j in i + 1..row.len() {
+                    let diff = row[j].0 - row[i].0;
+                    let acceleration = diff * (G * step / diff.length().powi(3));
+                    row[i].1 += acceleration * masses[j];
+                    row[j].1 -= acceleration * masses[i];
+                }
+            }
+            for (position, velocity) in &mut row {
+                *position += *velocity * step;
+            }
+            states.push(row.clone());
+        }
+
+        Ephemeris {
+            start,
+            step,
+            bodies: bodies.iter().map(|body| (body.0, body.1)).collect(),
+            states,
+        }
+    }
+
+    pub fn end(&self) -> f64 {
+        self.start + self.step * self.states.len().saturating_sub(1) as f64
+    }
+
+    pub fn covers(&self, time: f64) -> bool {
+        !self.states.is_empty() && time >= self.start && time <= self.end()
+    }
+
+    pub fn index_of(&self, entity: Entity) -> Option<usize> {
+        self.bodies.iter().position(|body| body.0 == entity)
+    }
+
+    /// State of a body at any time covered, interpolated linearly.
+    pub fn state(&self, index: usize, time: f64) -> (DVec3, DVec3) {
+        let at = ((time - self.start) / self.step).max(0.0);
+        let row = (at as usize).min(self.states.len() - 2);
+        let t = at - row as f64;
+        let (p0, v0) = self.states[row][index];
+        let (p1, v1) = self.states[row + 1][index];
+        (p0.lerp(p1, t), v0.lerp(v1, t))
+    }
+
+    /// Gravitational acceleration at a point.
+    pub fn acceleration(&self, time: f64, point: DVec3) -> DVec3 {
+        (0..self.bodies.len())
+            .map(|index| {
+                let diff = self.state(index, time).0 - point;
+                diff * (G * self.bodies[index].1 / diff.length().powi(3))
+            })
+            .sum()
+    }
+
+    /// Path of a massless body from the given state until the end of the
+    /// ephemeris, performing burns in the local frame of `primary` on the way.
+    pub fn predict(
+        &self,
+        time: f64,
+        coord: DVec3,
+        velocity: DVec3,
+        primary: usize,
+        burns: &[Burn],
+    ) -> Vec<(f64, DVec3, DVec3)> {
+        let (mut t, mut r, mut v) = (time, coord, velocity);
+        let mut path = vec![(t, r, v)];
+        if !self.covers(time) {
+            return path;
+        }
+
+        let end = self.end();
+        while t < end {
+            // Steps are cut short at burns so they happen at their exact time
+            let next = burns
+                .iter()
+                .map(|burn| burn.time)
+                .filter(|&burn_time| burn_time > t)
+                .fold((t + self.step).min(end), f64::min);
+            let h = next - t;
+            v += self.acceleration(t, r) * h;
+            r += v * h;
+            t = next;
+
+            for burn in burns.iter().filter(|burn| burn.time == t) {
+                let (primary_coord, primary_velocity) = self.state(primary, t);
+                v += burn.heading(r - primary_coord, v - primary_velocity) * burn.delta_v;
+            }
+            path.push((t, r, v));
+        }
+        path
+    }
+}
+
+/// Integrates the natural bodies ahead of the clock while a spacecraft
+/// exists, again once the clock gets close to the end of the previous run.
+pub fn update_ephemeris(
+    clock: Res<SimClock>,
+    mut ephemeris: ResMut<Ephemeris>,
+    mut jumps: EventReader<ClockJumped>,
+    bodies: Query<(Entity, &Mass, &Coord, &Velocity), Without<Spacecraft>>,
+    crafts: Query<(), With<Spacecraft>>,
+) {
+    let jumped = jumps.read().count() > 0;
+    if crafts.is_empty() {
+        return;
+    }
+    if !jumped && ephemeris.covers(clock.0 + PREDICTION_HORIZON) {
+        return;
+    }
+
+    let bodies: Vec<_> = bodies
+        .iter()
+        .map(|(entity, mass, coord, velocity)| (entity, mass.0, coord.0, velocity.0))
+        .collect();
+    let steps = ((PREDICTION_HORIZON + PREDICTION_REFRESH) / PREDICTION_STEP).ceil() as usize;
+    *ephemeris = Ephemeris::compute(clock.0, &bodies, PREDICTION_STEP, steps);
+}
@@ -0,0 +1,290 @@
+use bevy::{
+    math::DVec3,
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+};
+
+use crate::{
+    constants::*,
+    kepler::{period, propagate},
+    prediction::Ephemeris,
+    Coord, Kind, Mass, Primary, SimClock, Velocity,
+};
+
+/// Points drawn on each reference ellipse.
+const SEGMENTS: usize = 256;
+
+/// Two-body orbit a planet would follow around its primary from its state
+/// at `epoch`, if nothing but the two of them existed.
+#[derive(Component, Clone)]
+pub struct ReferenceOrbit {
+    primary: Entity,
+    mu: f64,
+    epoch: f64,
+    position: DVec3,
+    velocity: DVec3,
+}
+
+impl ReferenceOrbit {
+    /// Position and velocity relative to the primary at `time`.
+    pub fn state(&self, time: f64) -> (DVec3, DVec3) {
+        propagate(self.position, self.velocity, self.mu, time - self.epoch)
+    }
+
+    /// Angle by which `position`, relative to the primary, is ahead of the
+    /// reference at `time`.
+    pub fn phase_error(&self, time: f64, position: DVec3) -> f64 {
+        let (reference, velocity) = self.state(time);
+        let normal = reference.cross(velocity).normalize();
+        normal
+            .dot(reference.cross(position))
+            .atan2(reference.dot(position))
+    }
+}
+
+/// Phase errors of the integrators after `BENCHMARK_SPAN` of one planet
+/// alone with its primary.
+struct Benchmark {
+    name: String,
+    /// The `Physics` schedule's integrator at `DT`
+    physics: f64,
+    /// The ephemeris integrator at `PREDICTION_STEP`
+    ephemeris: f64,
+}
+
+/// `R` draws the reference orbits of the planets and lists how far each
+/// planet has drifted from its own, next to the phase error the integrators
+/// alone would have made. The benchmarks are run in the background the first
+/// time the orbits are shown.
+#[derive(Resource, Default)]
+pub struct ReferenceOrbits {
+    pub visible: bool,
+    benchmarks: Vec<Benchmark>,
+    running: Option<Task<Vec<Benchmark>>>,
+}
+
+#[derive(Component)]
+pub struct ReferencePanel;
+
+pub fn spawn_reference_orbits(
+    mut commands: Commands,
+    clock: Res<SimClock>,
+    planets: Query<(Entity, &Kind, &Mass, &Coord, &Velocity, &Primary)>,
+    primaries: Query<(&Mass, &Coord, &Velocity)>,
+) {
+    for (entity, kind, mass, coord, velocity, primary) in &planets {
+        if *kind != Kind::Planet {
+            continue;
+        }
+        let Ok((primary_mass, primary_coord, primary_velocity)) = primaries.get(primary.0) else {
+            continue;
+        };
+        commands.entity(entity).insert(ReferenceOrbit {
+            primary: primary.0,
+            mu: G * (primary_mass.0 + mass.0),
+            epoch: clock.0,
+            position: coord.0 - primary_coord.0,
+            velocity: velocity.0 - primary_velocity.0,
+        });
+    }
+}
+
+pub fn spawn_reference_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+        ReferencePanel,
+    ));
+}
+
+/// Integrates a planet and its primary alone the way the simulation does,
+/// and the way the ephemeris does.
+fn benchmark(name: &str, orbit: &ReferenceOrbit, mass: f64, primary_mass: f64) -> Benchmark {
+    let steps = (BENCHMARK_SPAN / DT as f64) as usize;
+    let (mut r1, mut r2) = (DVec3::ZERO, orbit.position);
+    let (mut v1, mut v2) = (DVec3::ZERO, orbit.velocity);
+    let dt = DT as f64;
+    for _ in 0..steps {
+        let diff = r2 - r1;
+        let direction = diff.normalize();
+        let distance_squared = diff.length_squared();
+        v1 += direction * (G * mass) / distance_squared * dt;
+        v2 += -direction * (G * primary_mass) / distance_squared * dt;
+        r1 += v1 * dt;
+        r2 += v2 * dt;
+    }
+    let physics = orbit.phase_error(orbit.epoch + steps as f64 * DT as f64, r2 - r1);
+
+    let steps = (BENCHMARK_SPAN / PREDICTION_STEP) as usize;
+    let ephemeris = Ephemeris::compute(
+        orbit.epoch,
+        &[
+            (orbit.primary, primary_mass, DVec3::ZERO, DVec3::ZERO),
+            (Entity::PLACEHOLDER, mass, orbit.position, orbit.velocity),
+        ],
+        PREDICTION_STEP,
+        steps,
+    );
+    let end = ephemeris.end();
+    let position = ephemeris.state(1, end).0 - ephemeris.state(0, end).0;
+
+    Benchmark {
+        name: name.to_string(),
+        physics,
+        ephemeris: orbit.phase_error(end, position),
+    }
+}
+
+fn arcseconds(angle: f64) -> f64 {
+    angle.to_degrees() * 3600.0
+}
+
+pub fn show_reference_orbits(
+    keyboard_input: Res<Input<KeyCode>>,
+    clock: Res<SimClock>,
+    mut references: ResMut<ReferenceOrbits>,
+    planets: Query<(&Name, &Mass, &Coord, &ReferenceOrbit)>,
+    primaries: Query<(&Mass, &Coord)>,
+    mut panel: Query<&mut Text, With<ReferencePanel>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::R) {
+        references.visible = !references.visible;
+    }
+    let text = &mut panel.single_mut().sections[0].value;
+    if !references.visible {
+        text.clear();
+        return;
+    }
+
+    if references.benchmarks.is_empty() && references.running.is_none() && !planets.is_empty() {
+        let inputs: Vec<_> = planets
+            .iter()
+            .filter_map(|(name, mass, _, orbit)| {
+                let (primary_mass, _) = primaries.get(orbit.primary).ok()?;
+                Some((name.to_string(), orbit.clone(), mass.0, primary_mass.0))
+            })
+            .collect();
+        references.running = Some(AsyncComputeTaskPool::get().spawn(async move {
+            inputs
+                .iter()
+                .map(|(name, orbit, mass, primary_mass)| {
+                    benchmark(name, orbit, *mass, *primary_mass)
+                })
+                .collect()
+        }));
+    }
+    if references
+        .running
+        .as_ref()
+        .is_some_and(|task| task.is_finished())
+    {
+        let task = references.running.take().unwrap();
+        references.benchmarks = block_on(task);
+    }
+
+    let mut lines = vec![if references.running.is_some() {
+        "Phase vs Kepler, \" (running integrator benchmarks)".to_string()
+    } else {
+        format!(
+            "Phase vs Kepler, \" (integrators over {:.0} d)",
+            BENCHMARK_SPAN / 86400.0
+        )
+    }];
+    for (name, _, coord, orbit) in &planets {
+        let Ok((_, primary_coord)) = primaries.get(orbit.primary) else {
+            continue;
+        };
+        let drift = orbit.phase_error(clock.0, coord.0 - primary_coord.0);
+        let mut line = format!("{}: {:+.1}", name, arcseconds(drift));
+        if let Some(benchmark) = references
+            .benchmarks
+            .iter()
+            .find(|benchmark| benchmark.name == name.as_str())
+        {
+            line += &format!(
+                " ({:+.1} / {:+.1})",
+                arcseconds(benchmark.physics),
+                arcseconds(benchmark.ephemeris)
+            );
+        }
+        lines.push(line);
+    }
+    *text = lines.join("\n");
+}
+
+/// Draws each reference ellipse around the primary's current position, and
+/// where the planet would be on it now.
+pub fn draw_reference_orbits(
+    mut gizmos: Gizmos,
+    clock: Res<SimClock>,
+    references: Res<ReferenceOrbits>,
+    planets: Query<&ReferenceOrbit>,
+    primaries: Query<&Coord>,
+    camera: Query<&Transform, With<Camera>>,
+) {
+    if !references.visible {
+        return;
+    }
+    let camera = camera.single();
+    for orbit in &planets {
+        let Ok(primary) = primaries.get(orbit.primary) else {
+            continue;
+        };
+        let center = primary.0;
+        let period = period(orbit.position, orbit.velocity, orbit.mu);
+        if !period.is_finite() {
+            continue;
+        }
+        let points = (0..=SEGMENTS).map(|i| {
+            let (position, _) = orbit.state(orbit.epoch + period * i as f64 / SEGMENTS as f64);
+            ((center + position) * SCALE).as_vec3()
+        });
+        gizmos.linestrip(points, Color::DARK_GREEN);
+
+        let (position, _) = orbit.state(clock.0);
+        let position = ((center + position) * SCALE).as_vec3();
+        gizmos.circle(
+            position,
+            camera.rotation * Vec3::Z,
+            camera.translation.distance(position) * 0.01,
+            Color::GREEN,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{setup, tests::headless_app};
+
+    #[test]
+    fn startup_gives_every_planet_a_reference_orbit() {
+        let mut app = headless_app();
+        app.init_resource::<SimClock>().add_systems(
+            Startup,
+            (setup, apply_deferred, spawn_reference_orbits).chain(),
+        );
+        app.update();
+        let world = &mut app.world;
+        let planets = world
+            .query::<&Kind>()
+            .iter(world)
+            .filter(|kind| **kind == Kind::Planet)
+            .count();
+        let orbits = world.query::<&ReferenceOrbit>().iter(world).count();
+        assert!(planets > 0);
+        assert_eq!(orbits, planets);
+    }
+}
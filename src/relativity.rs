@@ -0,0 +1,235 @@
+use std::f64::consts::TAU;
+
+use bevy::{
+    math::DVec3,
+    prelude::*,
+    tasks::{block_on, AsyncComputeTaskPool, Task},
+    utils::HashMap,
+};
+
+use crate::{
+    constants::*,
+    kepler::{period, Elements},
+    Coord, Kind, Mass, Primary, SimClock, Star, Velocity,
+};
+
+/// Orbits integrated, and steps per orbit, when measuring the perihelion
+/// advance of a planet alone with its star.
+const ORBITS: usize = 10;
+const STEPS_PER_ORBIT: usize = 50_000;
+
+/// `X` adds the first post-Newtonian correction to the attraction of stars;
+/// `A` shows how fast the perihelion of each planet advances.
+#[derive(Resource, Default)]
+pub struct Relativity {
+    pub enabled: bool,
+    pub visible: bool,
+    /// Clock and periapsis direction of each planet when the panel was opened
+    start: Option<(f64, HashMap<Entity, DVec3>)>,
+    /// Advance per century due to the correction, alongside its theoretical
+    /// value, by planet
+    advances: HashMap<Entity, (f64, f64)>,
+    /// Integrations of the advances, run in the background
+    measuring: Option<Task<HashMap<Entity, (f64, f64)>>>,
+}
+
+#[derive(Component)]
+pub struct PerihelionPanel;
+
+pub fn relativistic(relativity: Res<Relativity>) -> bool {
+    relativity.enabled
+}
+
+/// Schwarzschild correction to the acceleration of a test body at relative
+/// position `r` and velocity `v` around a mass of gravitational parameter
+/// `mu`, in harmonic coordinates.
+pub fn schwarzschild(r: DVec3, v: DVec3, mu: f64) -> DVec3 {
+    let distance = r.length();
+    (r * (4.0 * mu / distance - v.length_squared()) + v * (4.0 * r.dot(v)))
+        * (mu / (SPEED_OF_LIGHT.powi(2) * distance.powi(3)))
+}
+
+/// Adds the correction for every star to the velocity of every other body.
+pub fn relativistic_correction(
+    stars: Query<(&Mass, &Coord, &Velocity), With<Star>>,
+    mut bodies: Query<(&Coord, &mut Velocity), Without<Star>>,
+) {
+    for (coord, mut velocity) in &mut bodies {
+        let correction: DVec3 = stars
+            .iter()
+            .map(|(mass, star_coord, star_velocity)| {
+                schwarzschild(
+                    coord.0 - star_coord.0,
+                    velocity.0 - star_velocity.0,
+                    G * mass.0,
+                )
+            })
+            .sum();
+        velocity.0 += correction * DT as f64;
+    }
+}
+
+/// Rotation of the periapsis direction per century, found from the first and
+/// last periapsis passages of a two-body orbit integrated like the
+/// simulation does.
+fn perihelion_advance(r: DVec3, v: DVec3, mu: f64, relativistic: bool) -> f64 {
+    let step = period(r, v, mu) / STEPS_PER_ORBIT as f64;
+    let (mut r, mut v) = (r, v);
+    let mut passages = Vec::new();
+    let mut radial = r.dot(v);
+    for i in 0..ORBITS * STEPS_PER_ORBIT {
+        let mut acceleration = -r * mu / r.length().powi(3);
+        if relativistic {
+            acceleration += schwarzschild(r, v, mu);
+        }
+        v += acceleration * step;
+        r += v * step;
+        if radial < 0.0 && r.dot(v) >= 0.0 {
+            passages.push((i as f64 * step, Elements::from_state(r, v, mu)));
+        }
+        radial = r.dot(v);
+    }
+
+    let (Some((first, start)), Some((last, end))) = (passages.first(), passages.last()) else {
+        return 0.0;
+    };
+    if last <= first {
+        return 0.0;
+    }
+    let angle = start
+        .normal
+        .dot(start.periapsis.cross(end.periapsis))
+        .atan2(start.periapsis.dot(end.periapsis));
+    angle / (last - first) * CENTURY
+}
+
+fn arcseconds(angle: f64) -> f64 {
+    angle.to_degrees() * 3600.0
+}
+
+pub fn spawn_perihelion_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(25.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+        PerihelionPanel,
+    ));
+}
+
+/// Lists the perihelion advance of each planet in the running simulation
+/// since the panel was opened, and the part of it general relativity alone
+/// accounts for, measured on the planet's current orbit against the
+/// theoretical 6 pi mu / (c^2 p) per revolution.
+pub fn measure_perihelion_advance(
+    keyboard_input: Res<Input<KeyCode>>,
+    clock: Res<SimClock>,
+    mut relativity: ResMut<Relativity>,
+    planets: Query<(Entity, &Name, &Kind, &Mass, &Coord, &Velocity, &Primary)>,
+    stars: Query<(&Mass, &Coord, &Velocity), With<Star>>,
+    mut panel: Query<&mut Text, With<PerihelionPanel>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::X) {
+        relativity.enabled = !relativity.enabled;
+    }
+    if keyboard_input.just_pressed(KeyCode::A) {
+        relativity.visible = !relativity.visible;
+        relativity.start = None;
+        relativity.advances.clear();
+        relativity.measuring = None;
+    }
+    let text = &mut panel.single_mut().sections[0].value;
+    if !relativity.visible {
+        text.clear();
+        return;
+    }
+
+    let orbits: Vec<_> = planets
+        .iter()
+        .filter(|planet| *planet.2 == Kind::Planet)
+        .filter_map(|(entity, name, _, mass, coord, velocity, primary)| {
+            let (star_mass, star_coord, star_velocity) = stars.get(primary.0).ok()?;
+            let mu = G * (star_mass.0 + mass.0);
+            let (r, v) = (coord.0 - star_coord.0, velocity.0 - star_velocity.0);
+            Some((entity, name, mu, r, v))
+        })
+        .collect();
+
+    if relativity.start.is_none() {
+        let directions = orbits
+            .iter()
+            .map(|&(entity, _, mu, r, v)| (entity, Elements::from_state(r, v, mu).periapsis))
+            .collect();
+        relativity.start = Some((clock.0, directions));
+        let states: Vec<_> = orbits
+            .iter()
+            .map(|&(entity, _, mu, r, v)| (entity, mu, r, v))
+            .collect();
+        relativity.measuring = Some(AsyncComputeTaskPool::get().spawn(async move {
+            states
+                .into_iter()
+                .map(|(entity, mu, r, v)| {
+                    let newtonian = perihelion_advance(r, v, mu, false);
+                    let relativistic = perihelion_advance(r, v, mu, true);
+                    let elements = Elements::from_state(r, v, mu);
+                    let theory = TAU * 3.0 * mu
+                        / (SPEED_OF_LIGHT.powi(2) * elements.semi_latus_rectum)
+                        / period(r, v, mu)
+                        * CENTURY;
+                    (entity, (relativistic - newtonian, theory))
+                })
+                .collect()
+        }));
+    }
+    if relativity
+        .measuring
+        .as_ref()
+        .is_some_and(|task| task.is_finished())
+    {
+        let task = relativity.measuring.take().unwrap();
+        relativity.advances = block_on(task);
+    }
+    let Some((start, directions)) = &relativity.start else {
+        return;
+    };
+
+    let mut lines = vec![format!(
+        "Perihelion advance, \"/century (1PN {})",
+        if relativity.enabled { "on" } else { "off" }
+    )];
+    for (entity, name, mu, r, v) in orbits {
+        let elements = Elements::from_state(r, v, mu);
+        let elapsed = clock.0 - start;
+        let simulated = match directions.get(&entity) {
+            Some(direction) if elapsed > 0.0 => {
+                let angle = elements
+                    .normal
+                    .dot(direction.cross(elements.periapsis))
+                    .atan2(direction.dot(elements.periapsis));
+                format!("{:+.1}", arcseconds(angle / elapsed * CENTURY))
+            }
+            _ => "-".to_string(),
+        };
+        let gr = match relativity.advances.get(&entity) {
+            Some(&(relativistic, theory)) => format!(
+                "{:+.2} (theory {:.2})",
+                arcseconds(relativistic),
+                arcseconds(theory)
+            ),
+            None if relativity.measuring.is_some() => "measuring".to_string(),
+            None => continue,
+        };
+        lines.push(format!("{}: sim {}, GR {}", name, simulated, gr));
+    }
+    *text = lines.join("\n");
+}
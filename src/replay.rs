@@ -0,0 +1,510 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    ops::RangeInclusive,
+};
+
+use bevy::{math::DVec3, prelude::*};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::{
+    argument, constants::*, events::ClockJumped, format_time, scenarios::Scenario, Coord, SimClock,
+    Trajectory, Velocity,
+};
+
+const RECORDING_FILE: &str = "recording.bin.gz";
+const MAGIC: &[u8; 4] = b"SREC";
+/// Format of the recording files; files of another version are refused.
+pub const RECORDING_VERSION: u32 = 1;
+
+/// Position and velocity of every recorded body at one instant, by index
+/// into the recording's names; `None` for bodies that did not exist yet.
+struct Frame {
+    time: f64,
+    states: Vec<Option<(DVec3, DVec3)>>,
+}
+
+/// Body states sampled at a fixed cadence over a run.
+pub struct Recording {
+    scenario: String,
+    cadence: f64,
+    names: Vec<String>,
+    frames: Vec<Frame>,
+}
+
+impl Recording {
+    fn new(scenario: &str, cadence: f64) -> Self {
+        Recording {
+            scenario: scenario.to_string(),
+            cadence,
+            names: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn start(&self) -> f64 {
+        self.frames.first().map_or(0.0, |frame| frame.time)
+    }
+
+    pub fn end(&self) -> f64 {
+        self.frames.last().map_or(0.0, |frame| frame.time)
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Index of the last frame at or before `time`.
+    fn frame_at(&self, time: f64) -> usize {
+        self.frames
+            .partition_point(|frame| frame.time <= time)
+            .saturating_sub(1)
+    }
+
+    /// State of the body with the given name index at `time`, interpolated
+    /// between the frames around it with cubic Hermite splines, which match
+    /// both the recorded positions and velocities.
+    fn state(&self, index: usize, time: f64) -> Option<(DVec3, DVec3)> {
+        let i = self.frame_at(time);
+        let (a, b) = (self.frames.get(i)?, self.frames.get(i + 1));
+        let (p0, v0) = (*a.states.get(index)?)?;
+        let Some(((p1, v1), b)) = b.and_then(|b| Some(((*b.states.get(index)?)?, b))) else {
+            return Some((p0, v0));
+        };
+        let h = b.time - a.time;
+        let s = ((time - a.time) / h).clamp(0.0, 1.0);
+        let (s2, s3) = (s * s, s * s * s);
+        let position = p0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+            + v0 * (h * (s3 - 2.0 * s2 + s))
+            + p1 * (3.0 * s2 - 2.0 * s3)
+            + v1 * (h * (s3 - s2));
+        let velocity = (p0 - p1) * ((6.0 * s2 - 6.0 * s) / h)
+            + v0 * (3.0 * s2 - 4.0 * s + 1.0)
+            + v1 * (3.0 * s2 - 2.0 * s);
+        Some((position, velocity))
+    }
+
+    /// Name, position and velocity at `time` of every body recorded then.
+    pub fn states(&self, time: f64) -> impl Iterator<Item = (&str, DVec3, DVec3)> {
+        self.names
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, name)| {
+                let (position, velocity) = self.state(index, time)?;
+                Some((name.as_str(), position, velocity))
+            })
+    }
+
+    /// Recorded positions of a body over the given frames.
+    fn path(&self, index: usize, frames: RangeInclusive<usize>) -> Vec<DVec3> {
+        self.frames[frames]
+            .iter()
+            .filter_map(|frame| frame.states.get(index).copied().flatten())
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Writes the recording as little-endian binary, gzip compressed.
+    fn write(&self, path: &str) -> io::Result<()> {
+        let mut out = GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+        out.write_all(MAGIC)?;
+        out.write_all(&RECORDING_VERSION.to_le_bytes())?;
+        write_string(&mut out, &self.scenario)?;
+        out.write_all(&self.cadence.to_le_bytes())?;
+        out.write_all(&(self.names.len() as u32).to_le_bytes())?;
+        for name in &self.names {
+            write_string(&mut out, name)?;
+        }
+        out.write_all(&(self.frames.len() as u32).to_le_bytes())?;
+        for frame in &self.frames {
+            out.write_all(&frame.time.to_le_bytes())?;
+            out.write_all(&(frame.states.len() as u32).to_le_bytes())?;
+            for state in &frame.states {
+                out.write_all(&[state.is_some() as u8])?;
+                if let Some((position, velocity)) = state {
+                    for value in position.to_array().into_iter().chain(velocity.to_array()) {
+                        out.write_all(&value.to_le_bytes())?;
+                    }
+                }
+            }
+        }
+        out.finish()?.flush()
+    }
+
+    pub fn read(path: &str) -> io::Result<Self> {
+        let mut input = GzDecoder::new(BufReader::new(File::open(path)?));
+        let mut magic = [0; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a recording",
+            ));
+        }
+        let version = read_u32(&mut input)?;
+        if version != RECORDING_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "version {} is not supported, expected {}",
+                    version, RECORDING_VERSION
+                ),
+            ));
+        }
+        let scenario = read_string(&mut input)?;
+        let cadence = read_f64(&mut input)?;
+        let names = (0..read_u32(&mut input)?)
+            .map(|_| read_string(&mut input))
+            .collect::<io::Result<_>>()?;
+        let mut frames = Vec::new();
+        for _ in 0..read_u32(&mut input)? {
+            let time = read_f64(&mut input)?;
+            let mut states = Vec::new();
+            for _ in 0..read_u32(&mut input)? {
+                let mut present = [0];
+                input.read_exact(&mut present)?;
+                states.push(match present[0] {
+                    0 => None,
+                    _ => Some((read_dvec3(&mut input)?, read_dvec3(&mut input)?)),
+                });
+            }
+            frames.push(Frame { time, states });
+        }
+        Ok(Recording {
+            scenario,
+            cadence,
+            names,
+            frames,
+        })
+    }
+}
+
+fn write_string(out: &mut impl Write, value: &str) -> io::Result<()> {
+    out.write_all(&(value.len() as u32).to_le_bytes())?;
+    out.write_all(value.as_bytes())
+}
+
+fn read_u32(input: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f64(input: &mut impl Read) -> io::Result<f64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+    Ok(f64::from_le_bytes(bytes))
+}
+
+fn read_dvec3(input: &mut impl Read) -> io::Result<DVec3> {
+    Ok(DVec3::new(
+        read_f64(input)?,
+        read_f64(input)?,
+        read_f64(input)?,
+    ))
+}
+
+fn read_string(input: &mut impl Read) -> io::Result<String> {
+    let mut bytes = vec![0; read_u32(input)? as usize];
+    input.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// `I` starts recording the run to `recording.bin.gz`, sampling every
+/// `cadence` simulated seconds, and stops and writes it when pressed again.
+#[derive(Resource)]
+pub struct Recorder {
+    pub cadence: f64,
+    recording: Option<Recording>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Recorder {
+            cadence: RECORD_CADENCE,
+            recording: None,
+        }
+    }
+}
+
+impl Recorder {
+    /// Records at the cadence in seconds following `--record-cadence` on the
+    /// command line, if given.
+    pub fn from_args() -> Self {
+        let cadence = argument("--record-cadence").and_then(|cadence| cadence.parse().ok());
+        Recorder {
+            cadence: cadence
+                .filter(|&cadence| cadence > 0.0)
+                .unwrap_or(RECORD_CADENCE),
+            recording: None,
+        }
+    }
+}
+
+/// A recording being played back instead of the simulation, at `speed`
+/// simulated seconds per second, negative when playing backward.
+#[derive(Resource, Default)]
+pub struct Replay {
+    recording: Option<Recording>,
+    pub time: f64,
+    pub speed: f64,
+    pub paused: bool,
+    /// Frame the trails were last drawn up to
+    shown: Option<usize>,
+}
+
+impl Replay {
+    /// Plays the recording whose path follows `--replay` on the command line.
+    pub fn from_args() -> Self {
+        let Some(path) = argument("--replay") else {
+            return Replay::default();
+        };
+        match Recording::read(&path) {
+            Ok(recording) => Replay::new(recording),
+            Err(err) => {
+                warn!("Could not load {}: {}", path, err);
+                Replay::default()
+            }
+        }
+    }
+
+    fn new(recording: Recording) -> Self {
+        Replay {
+            time: recording.start(),
+            // One timestep per fixed update, as the simulation runs unwarped
+            speed: TIMESTEP as f64 / Time::<Fixed>::default().timestep().as_secs_f64(),
+            paused: false,
+            shown: None,
+            recording: Some(recording),
+        }
+    }
+}
+
+/// Whether the simulation is running rather than a replay.
+pub fn live(replay: Res<Replay>) -> bool {
+    replay.recording.is_none()
+}
+
+#[derive(Component)]
+pub struct ReplayPanel;
+
+pub fn control_recording(
+    keyboard_input: Res<Input<KeyCode>>,
+    scenario: Res<Scenario>,
+    replay: Res<Replay>,
+    mut recorder: ResMut<Recorder>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::I) || replay.recording.is_some() {
+        return;
+    }
+    match recorder.recording.take() {
+        Some(recording) => match recording.write(RECORDING_FILE) {
+            Ok(()) => info!(
+                "Recorded {} frames to {}",
+                recording.frames.len(),
+                RECORDING_FILE
+            ),
+            Err(err) => warn!("Could not write {}: {}", RECORDING_FILE, err),
+        },
+        None => recorder.recording = Some(Recording::new(scenario.name, recorder.cadence)),
+    }
+}
+
+/// Samples every body once `cadence` has passed since the last frame. Frames
+/// after the clock, left behind when it jumps back, are dropped.
+pub fn record_frames(
+    clock: Res<SimClock>,
+    mut recorder: ResMut<Recorder>,
+    bodies: Query<(&Name, &Coord, &Velocity)>,
+) {
+    let Some(recording) = &mut recorder.recording else {
+        return;
+    };
+    let now = clock.0;
+    let kept = recording.frames.partition_point(|frame| frame.time <= now);
+    recording.frames.truncate(kept);
+    if let Some(last) = recording.frames.last() {
+        if now - last.time < recording.cadence {
+            return;
+        }
+    }
+
+    let mut states = vec![None; recording.names.len()];
+    for (name, coord, velocity) in &bodies {
+        let index = match recording.names.iter().position(|n| n == name.as_str()) {
+            Some(index) => index,
+            None => {
+                recording.names.push(name.to_string());
+                states.push(None);
+                recording.names.len() - 1
+            }
+        };
+        states[index] = Some((coord.0, velocity.0));
+    }
+    recording.frames.push(Frame { time: now, states });
+}
+
+/// `W` replays `recording.bin.gz`, and pressing it again resumes the
+/// simulation from the moment shown. While replaying, `Space` pauses,
+/// `,` / `.` halve / double the speed, `/` reverses it, `Home` / `End` seek to
+/// the start / end and `PageUp` / `PageDown` step back / forward a tenth of
+/// the recording.
+pub fn control_replay(
+    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    recorder: Res<Recorder>,
+    mut replay: ResMut<Replay>,
+    mut jumps: EventWriter<ClockJumped>,
+) {
+    if keyboard_input.just_pressed(KeyCode::W) {
+        if replay.recording.take().is_some() {
+            jumps.send(ClockJumped);
+            return;
+        }
+        if recorder.recording.is_some() {
+            warn!("Stop recording before replaying");
+            return;
+        }
+        match Recording::read(RECORDING_FILE) {
+            Ok(recording) => *replay = Replay::new(recording),
+            Err(err) => warn!("Could not load {}: {}", RECORDING_FILE, err),
+        }
+        return;
+    }
+    let Some(recording) = &replay.recording else {
+        return;
+    };
+    let (start, end) = (recording.start(), recording.end());
+    let step = (end - start) / 10.0;
+
+    let mut target = replay.time;
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        replay.paused = !replay.paused;
+    }
+    if keyboard_input.just_pressed(KeyCode::Comma) {
+        replay.speed /= 2.0;
+    }
+    if keyboard_input.just_pressed(KeyCode::Period) {
+        replay.speed *= 2.0;
+    }
+    if keyboard_input.just_pressed(KeyCode::Slash) {
+        replay.speed = -replay.speed;
+    }
+    if keyboard_input.just_pressed(KeyCode::Home) {
+        target = start;
+    }
+    if keyboard_input.just_pressed(KeyCode::End) {
+        target = end;
+    }
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        target -= step;
+    }
+    if keyboard_input.just_pressed(KeyCode::PageDown) {
+        target += step;
+    }
+    if !replay.paused {
+        target += replay.speed * time.delta_seconds_f64();
+    }
+    replay.time = target.clamp(start, end);
+}
+
+/// Moves every recorded body to where it was at the replay time, and its
+/// trail to the path it had recorded by then, for the rendering, labels and
+/// camera to follow as they do the simulation.
+pub fn play_replay(
+    mut replay: ResMut<Replay>,
+    mut clock: ResMut<SimClock>,
+    mut bodies: Query<(
+        &Name,
+        &mut Coord,
+        &mut Velocity,
+        &mut Transform,
+        &mut Trajectory,
+    )>,
+) {
+    let time = replay.time;
+    let Some(recording) = &replay.recording else {
+        return;
+    };
+    let frame = recording.frame_at(time);
+    // Trails grow by the frames passed while playing forward, and are drawn
+    // again from the start after going back
+    let frames = match replay.shown {
+        Some(shown) if shown == frame => None,
+        Some(shown) if shown < frame => Some(shown + 1..=frame),
+        _ => Some(0..=frame),
+    };
+
+    for (name, mut coord, mut velocity, mut transform, mut trajectory) in &mut bodies {
+        let Some(index) = recording.names.iter().position(|n| n == name.as_str()) else {
+            continue;
+        };
+        let Some((position, motion)) = recording.state(index, time) else {
+            continue;
+        };
+        coord.0 = position;
+        velocity.0 = motion;
+        transform.translation = (position * SCALE).as_vec3();
+        match &frames {
+            Some(frames) if *frames.start() > 0 => {
+                trajectory.0.extend(recording.path(index, frames.clone()))
+            }
+            Some(frames) => trajectory.0 = recording.path(index, frames.clone()),
+            None => {}
+        }
+    }
+    clock.0 = time;
+    replay.shown = Some(frame);
+}
+
+pub fn spawn_replay_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(30.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+        ReplayPanel,
+    ));
+}
+
+pub fn update_replay_panel(
+    recorder: Res<Recorder>,
+    replay: Res<Replay>,
+    scenario: Res<Scenario>,
+    mut panel: Query<&mut Text, With<ReplayPanel>>,
+) {
+    let text = &mut panel.single_mut().sections[0].value;
+    *text = match (&recorder.recording, &replay.recording) {
+        (_, Some(recording)) => {
+            let mismatch = if recording.scenario != scenario.name {
+                format!(" (recorded in {})", recording.scenario)
+            } else {
+                String::new()
+            };
+            format!(
+                "Replay {} / {}, {:.0} s/s{}{}",
+                format_time(replay.time),
+                format_time(recording.end()),
+                replay.speed,
+                if replay.paused { " paused" } else { "" },
+                mismatch
+            )
+        }
+        (Some(recording), None) => format!(
+            "Recording every {:.0} s, {} frames",
+            recording.cadence,
+            recording.frames.len()
+        ),
+        (None, None) => String::new(),
+    };
+}
@@ -0,0 +1,236 @@
+use std::f32::consts::TAU;
+
+use bevy::{
+    pbr::NotShadowCaster,
+    prelude::*,
+    render::{
+        mesh::Indices,
+        render_resource::{Extent3d, PrimitiveTopology, TextureDimension, TextureFormat},
+        view::RenderLayers,
+    },
+};
+
+use crate::constants::*;
+
+/// A single ring or gap-free band, distances in meters from the planet center.
+pub struct RingBand {
+    pub inner: f64,
+    pub outer: f64,
+    pub opacity: f32,
+}
+
+/// Ring system of a body, drawn in the body's equatorial plane so it follows its `Spin`.
+pub struct RingSpec {
+    pub body: &'static str,
+    pub color: Color,
+    pub bands: &'static [RingBand],
+}
+
+const fn band(inner_km: f64, outer_km: f64, opacity: f32) -> RingBand {
+    RingBand {
+        inner: inner_km * 1e3,
+        outer: outer_km * 1e3,
+        opacity,
+    }
+}
+
+pub const RING_SYSTEMS: &[RingSpec] = &[
+    RingSpec {
+        body: "Saturn",
+        color: Color::rgb(0.82, 0.75, 0.6),
+        bands: &[
+            band(66_900.0, 74_510.0, 0.05),   // D
+            band(74_658.0, 92_000.0, 0.3),    // C
+            band(92_000.0, 117_580.0, 0.9),   // B
+            band(117_580.0, 122_170.0, 0.05), // Cassini Division
+            band(122_170.0, 133_423.0, 0.65), // A
+            band(133_748.0, 136_775.0, 0.55), // A beyond the Encke Gap
+            band(139_926.0, 140_426.0, 0.45), // F
+        ],
+    },
+    RingSpec {
+        body: "Uranus",
+        color: Color::rgb(0.35, 0.35, 0.37),
+        bands: &[
+            band(41_787.0, 41_887.0, 0.3),  // 6
+            band(42_184.0, 42_284.0, 0.3),  // 5
+            band(42_521.0, 42_621.0, 0.3),  // 4
+            band(44_668.0, 44_768.0, 0.35), // Alpha
+            band(45_611.0, 45_711.0, 0.35), // Beta
+            band(47_126.0, 47_226.0, 0.3),  // Eta
+            band(47_577.0, 47_677.0, 0.35), // Gamma
+            band(48_250.0, 48_350.0, 0.35), // Delta
+            band(49_973.0, 50_073.0, 0.2),  // Lambda
+            band(51_100.0, 51_200.0, 0.6),  // Epsilon
+        ],
+    },
+    RingSpec {
+        body: "Neptune",
+        color: Color::rgb(0.4, 0.33, 0.3),
+        bands: &[
+            band(40_900.0, 42_900.0, 0.04), // Galle
+            band(53_150.0, 53_250.0, 0.2),  // Le Verrier
+            band(53_200.0, 57_200.0, 0.03), // Lassell
+            band(57_150.0, 57_250.0, 0.1),  // Arago
+            band(62_882.0, 62_982.0, 0.3),  // Adams
+        ],
+    },
+];
+
+/// Radial resolution of the generated ring textures.
+const RING_TEXTURE_WIDTH: u32 = 1024;
+
+/// Render layer seen by the star lights but not the camera, holding the
+/// opaque copies of the rings that cast their shadows.
+pub const SHADOW_LAYER: u8 = 1;
+
+/// Opacity above which a ring casts a shadow.
+const SHADOW_CUTOFF: f32 = 0.5;
+
+pub fn spawn_rings(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    bodies: Query<(Entity, &Name)>,
+) {
+    for spec in RING_SYSTEMS {
+        let Some((body, _)) = bodies.iter().find(|(_, name)| name.as_str() == spec.body) else {
+            continue;
+        };
+
+        let inner = spec.bands.iter().map(|b| b.inner).fold(f64::MAX, f64::min);
+        let outer = spec.bands.iter().map(|b| b.outer).fold(0.0, f64::max);
+
+        let mesh = meshes.add(ring_mesh(
+            (inner * SCALE) as f32,
+            (outer * SCALE) as f32,
+            256,
+        ));
+        let texture = images.add(ring_texture(spec, inner, outer));
+        let ring = commands
+            .spawn((
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: materials.add(StandardMaterial {
+                        base_color: spec.color,
+                        base_color_texture: Some(texture.clone()),
+                        alpha_mode: AlphaMode::Blend,
+                        double_sided: true,
+                        cull_mode: None,
+                        perceptual_roughness: 1.0,
+                        ..default()
+                    }),
+                    ..default()
+                },
+                NotShadowCaster,
+            ))
+            .id();
+        // A blended ring would shadow wherever it is more than faintly opaque;
+        // the shadow comes from a masked copy only the star lights see
+        let shadow = commands
+            .spawn((
+                PbrBundle {
+                    mesh,
+                    material: materials.add(StandardMaterial {
+                        base_color_texture: Some(texture),
+                        alpha_mode: AlphaMode::Mask(SHADOW_CUTOFF),
+                        double_sided: true,
+                        cull_mode: None,
+                        ..default()
+                    }),
+                    ..default()
+                },
+                RenderLayers::layer(SHADOW_LAYER),
+            ))
+            .id();
+        commands.entity(body).push_children(&[ring, shadow]);
+    }
+}
+
+/// Flat annulus in the XY plane facing +Z, with `u` running from the inner to the outer edge.
+fn ring_mesh(inner: f32, outer: f32, segments: u32) -> Mesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+
+    for i in 0..=segments {
+        let v = i as f32 / segments as f32;
+        let (sin, cos) = (v * TAU).sin_cos();
+        for (radius, u) in [(inner, 0.0), (outer, 1.0)] {
+            positions.push([cos * radius, sin * radius, 0.0]);
+            normals.push([0.0, 0.0, 1.0]);
+            uvs.push([u, v]);
+        }
+    }
+
+    for i in 0..segments {
+        let base = i * 2;
+        indices.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh
+}
+
+/// Radial opacity profile: each texel takes the opacity of the bands covering it,
+/// weighted by coverage so rings narrower than a texel stay faint instead of vanishing.
+fn ring_texture(spec: &RingSpec, inner: f64, outer: f64) -> Image {
+    let texel = (outer - inner) / RING_TEXTURE_WIDTH as f64;
+    let mut data = Vec::with_capacity(RING_TEXTURE_WIDTH as usize * 4);
+
+    for i in 0..RING_TEXTURE_WIDTH {
+        let from = inner + texel * i as f64;
+        let to = from + texel;
+        let alpha = spec
+            .bands
+            .iter()
+            .map(|b| {
+                let overlap = (to.min(b.outer) - from.max(b.inner)).max(0.0) / texel;
+                b.opacity * overlap as f32
+            })
+            .sum::<f32>()
+            .min(1.0);
+        // Slight ringlet structure so wide bands do not look flat
+        let ripple = 0.85 + 0.15 * (i as f32 * 0.9).sin() * (i as f32 * 0.23).cos();
+        let shade = (255.0 * ripple) as u8;
+        data.extend_from_slice(&[shade, shade, shade, (255.0 * alpha * ripple) as u8]);
+    }
+
+    Image::new(
+        Extent3d {
+            width: RING_TEXTURE_WIDTH,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{setup, tests::headless_app};
+
+    #[test]
+    fn startup_gives_saturn_rings() {
+        let mut app = headless_app();
+        app.add_systems(Startup, (setup, apply_deferred, spawn_rings).chain());
+        app.update();
+        let world = &mut app.world;
+        let saturn = world
+            .query::<(&Name, &Children)>()
+            .iter(world)
+            .find(|(name, _)| name.as_str() == "Saturn")
+            .map(|(_, children)| children.len());
+        // The ring and the copy casting its shadow
+        assert_eq!(saturn, Some(2));
+    }
+}
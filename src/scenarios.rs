@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use crate::{
+    argument,
+    bodies::{BodySpec, ALPHA_CENTAURI, KEPLER_16, SOLAR_SYSTEM, TRAPPIST_1},
+};
+
+/// A system of bodies the simulation can start from, picked with
+/// `--scenario <name>`.
+#[derive(Resource, Clone, Copy)]
+pub struct Scenario {
+    pub name: &'static str,
+    pub bodies: &'static [BodySpec],
+    /// Initial distance of the camera from the first body, AU
+    pub view_distance: f64,
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "solar-system",
+        bodies: SOLAR_SYSTEM,
+        view_distance: 3.0,
+    },
+    Scenario {
+        name: "alpha-centauri",
+        bodies: ALPHA_CENTAURI,
+        view_distance: 40.0,
+    },
+    Scenario {
+        name: "kepler-16",
+        bodies: KEPLER_16,
+        view_distance: 2.0,
+    },
+    Scenario {
+        name: "trappist-1",
+        bodies: TRAPPIST_1,
+        view_distance: 0.15,
+    },
+];
+
+impl Default for Scenario {
+    fn default() -> Self {
+        SCENARIOS[0]
+    }
+}
+
+impl Scenario {
+    pub fn find(name: &str) -> Option<Scenario> {
+        SCENARIOS
+            .iter()
+            .find(|scenario| scenario.name.eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    /// The scenario named after `--scenario` on the command line, the Solar
+    /// System when there is none or it is unknown.
+    pub fn from_args() -> Self {
+        let Some(name) = argument("--scenario") else {
+            return Scenario::default();
+        };
+        Scenario::find(&name).unwrap_or_else(|| {
+            let names: Vec<_> = SCENARIOS.iter().map(|scenario| scenario.name).collect();
+            warn!(
+                "Unknown scenario {}, expected one of: {}",
+                name,
+                names.join(", ")
+            );
+            Scenario::default()
+        })
+    }
+}
@@ -0,0 +1,334 @@
+use std::{collections::HashSet, error::Error, fs};
+
+use bevy::{math::DVec3, prelude::*, utils::HashMap};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    argument,
+    constants::*,
+    events::ClockJumped,
+    forces::ForceStates,
+    lagrange::spawn_particle,
+    patched::Propagation,
+    scenarios::Scenario,
+    spacecraft::{spawn_craft, Spacecraft},
+    Coord, Kind, Label, Mass, Ordinal, Primary, SimClock, Trajectory, Velocity,
+};
+
+/// Format of the snapshot files; files of another version are refused.
+pub const SNAPSHOT_VERSION: u32 = 2;
+const QUICKSAVE_FILE: &str = "quicksave.ron";
+
+/// State of a body, matched by name when the snapshot is restored.
+#[derive(Serialize, Deserialize)]
+struct BodySnapshot {
+    name: String,
+    kind: Kind,
+    /// Name of the body it orbits
+    primary: Option<String>,
+    coord: DVec3,
+    velocity: DVec3,
+    mass: f64,
+    trajectory: Vec<DVec3>,
+    /// Propellant, engine and planned burns of a spacecraft
+    spacecraft: Option<Spacecraft>,
+    /// Non-gravitational forces acting on the body, and whether each is
+    /// enabled
+    forces: Vec<(String, bool)>,
+}
+
+/// Everything needed to resume a run where it was saved.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    pub scenario: String,
+    clock: f64,
+    /// Name of the body the camera follows
+    focus: Option<String>,
+    patched_conics: bool,
+    warp: u32,
+    bodies: Vec<BodySnapshot>,
+}
+
+/// Read ahead of the rest, so files of another version fail with a clear
+/// message rather than on the first field that changed.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl Snapshot {
+    pub fn read(path: &str) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let header: Header = ron::from_str(&text)?;
+        if header.version != SNAPSHOT_VERSION {
+            return Err(format!(
+                "version {} is not supported, expected {}",
+                header.version, SNAPSHOT_VERSION
+            )
+            .into());
+        }
+        let snapshot: Snapshot = ron::from_str(&text)?;
+        snapshot.check_names()?;
+        Ok(snapshot)
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        self.check_names()?;
+        let config = PrettyConfig::default().compact_arrays(true);
+        fs::write(path, ron::ser::to_string_pretty(self, config)?)?;
+        Ok(())
+    }
+
+    /// Bodies are matched by name, so each name may appear only once.
+    fn check_names(&self) -> Result<(), String> {
+        let mut names = HashSet::new();
+        match self.bodies.iter().find(|body| !names.insert(&body.name)) {
+            Some(body) => Err(format!("{} appears more than once", body.name)),
+            None => Ok(()),
+        }
+    }
+
+    /// The snapshot whose path follows `--snapshot` on the command line.
+    pub fn from_args() -> Option<Self> {
+        let path = argument("--snapshot")?;
+        Snapshot::read(&path)
+            .map_err(|err| warn!("Could not load {}: {}", path, err))
+            .ok()
+    }
+}
+
+/// Snapshot waiting to be restored once the bodies it names exist.
+#[derive(Resource, Default)]
+pub struct Snapshots {
+    pub pending: Option<Snapshot>,
+    /// Body the camera goes back to once the bodies restored with the last
+    /// snapshot have been spawned
+    pub focus: Option<String>,
+}
+
+type SavedBody<'a> = (
+    Entity,
+    &'a Name,
+    &'a Kind,
+    Option<&'a Primary>,
+    &'a Coord,
+    &'a Velocity,
+    &'a Mass,
+    &'a Trajectory,
+    Option<&'a Spacecraft>,
+);
+
+type RestoredBody<'a> = (
+    Entity,
+    &'a Name,
+    &'a Kind,
+    &'a mut Coord,
+    &'a mut Velocity,
+    &'a mut Mass,
+    &'a mut Transform,
+    &'a mut Trajectory,
+    Option<&'a mut Primary>,
+    Option<&'a mut Spacecraft>,
+);
+
+/// `F5` saves the run to `quicksave.ron`, `F9` restores it.
+#[allow(clippy::too_many_arguments)]
+pub fn quick_save(
+    keyboard_input: Res<Input<KeyCode>>,
+    scenario: Res<Scenario>,
+    clock: Res<SimClock>,
+    propagation: Res<Propagation>,
+    forces: Res<ForceStates>,
+    mut snapshots: ResMut<Snapshots>,
+    bodies: Query<SavedBody>,
+    names: Query<&Name>,
+    focusable: Query<&Name, With<Mass>>,
+    camera: Query<&Ordinal>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F5) {
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            scenario: scenario.name.to_string(),
+            clock: clock.0,
+            focus: focusable
+                .iter()
+                .nth(camera.single().0)
+                .map(|name| name.to_string()),
+            patched_conics: propagation.patched_conics,
+            warp: propagation.warp,
+            bodies: bodies
+                .iter()
+                .map(
+                    |(entity, name, kind, primary, coord, velocity, mass, trajectory, craft)| {
+                        let mut attached: Vec<_> = forces
+                            .attached
+                            .iter()
+                            .filter(|((body, _), _)| *body == entity)
+                            .map(|((_, force), &enabled)| (force.to_string(), enabled))
+                            .collect();
+                        attached.sort();
+                        BodySnapshot {
+                            name: name.to_string(),
+                            kind: *kind,
+                            primary: primary
+                                .and_then(|primary| names.get(primary.0).ok())
+                                .map(|name| name.to_string()),
+                            coord: coord.0,
+                            velocity: velocity.0,
+                            mass: mass.0,
+                            trajectory: trajectory.0.clone(),
+                            spacecraft: craft.cloned(),
+                            forces: attached,
+                        }
+                    },
+                )
+                .collect(),
+        };
+        match snapshot.write(QUICKSAVE_FILE) {
+            Ok(()) => info!("Saved {}", QUICKSAVE_FILE),
+            Err(err) => warn!("Could not write {}: {}", QUICKSAVE_FILE, err),
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F9) {
+        match Snapshot::read(QUICKSAVE_FILE) {
+            Ok(snapshot) => snapshots.pending = Some(snapshot),
+            Err(err) => warn!("Could not load {}: {}", QUICKSAVE_FILE, err),
+        }
+    }
+}
+
+/// Puts every body named in the pending snapshot back in its saved state and
+/// winds the clock to when it was saved. Spacecraft and test particles the
+/// snapshot names are spawned again if they are gone, and those it does not
+/// name are removed.
+#[allow(clippy::too_many_arguments)]
+pub fn restore_snapshot(
+    mut commands: Commands,
+    mut snapshots: ResMut<Snapshots>,
+    scenario: Res<Scenario>,
+    mut clock: ResMut<SimClock>,
+    mut propagation: ResMut<Propagation>,
+    mut forces: ResMut<ForceStates>,
+    mut jumps: EventWriter<ClockJumped>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut bodies: Query<RestoredBody>,
+    labels: Query<(Entity, &Label)>,
+    focusable: Query<&Name, With<Mass>>,
+    mut camera: Query<&mut Ordinal>,
+) {
+    forces.pending.clear();
+    if let Some(focus) = snapshots.focus.take() {
+        if let Some(index) = focusable.iter().position(|name| name.as_str() == focus) {
+            camera.single_mut().0 = index;
+        }
+    }
+
+    let Some(snapshot) = snapshots.pending.take() else {
+        return;
+    };
+    if snapshot.scenario != scenario.name {
+        warn!(
+            "Snapshot of {} cannot be restored into {}",
+            snapshot.scenario, scenario.name
+        );
+        return;
+    }
+
+    let saved: HashSet<_> = snapshot
+        .bodies
+        .iter()
+        .map(|body| body.name.as_str())
+        .collect();
+    let mut entities = HashMap::new();
+    for (entity, name, kind, ..) in &bodies {
+        if saved.contains(name.as_str()) {
+            entities.insert(name.to_string(), entity);
+        } else if matches!(kind, Kind::Spacecraft | Kind::Particle) {
+            commands.entity(entity).despawn_recursive();
+            for (label, _) in labels.iter().filter(|(_, label)| label.entity == entity) {
+                commands.entity(label).despawn_recursive();
+            }
+        }
+    }
+
+    for body in snapshot.bodies {
+        let primary = body
+            .primary
+            .as_ref()
+            .and_then(|primary| entities.get(primary).copied());
+        let existing = entities
+            .get(&body.name)
+            .and_then(|&entity| bodies.get_mut(entity).ok());
+        let entity = match existing {
+            Some((
+                entity,
+                ..,
+                mut coord,
+                mut velocity,
+                mut mass,
+                mut transform,
+                mut trajectory,
+                body_primary,
+                craft,
+            )) => {
+                coord.0 = body.coord;
+                velocity.0 = body.velocity;
+                transform.translation = (body.coord * SCALE).as_vec3();
+                trajectory.0 = body.trajectory;
+                if let (Some(mut body_primary), Some(primary)) = (body_primary, primary) {
+                    body_primary.0 = primary;
+                }
+                mass.0 = body.mass;
+                if let (Some(mut craft), Some(saved)) = (craft, body.spacecraft) {
+                    mass.0 = saved.dry_mass + saved.propellant;
+                    *craft = saved;
+                }
+                entity
+            }
+            None => {
+                let spawned = match (body.kind, primary, body.spacecraft) {
+                    (Kind::Spacecraft, Some(primary), Some(craft)) => spawn_craft(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        &asset_server,
+                        &body.name,
+                        primary,
+                        body.coord,
+                        body.velocity,
+                        craft,
+                    ),
+                    (Kind::Particle, Some(primary), _) => spawn_particle(
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        &asset_server,
+                        &body.name,
+                        primary,
+                        body.coord,
+                        body.velocity,
+                    ),
+                    _ => {
+                        warn!("{} is not in the simulation, left out", body.name);
+                        continue;
+                    }
+                };
+                commands.entity(spawned).insert(Trajectory(body.trajectory));
+                entities.insert(body.name, spawned);
+                spawned
+            }
+        };
+        forces.pending.insert(entity, body.forces);
+    }
+
+    propagation.patched_conics = snapshot.patched_conics;
+    propagation.warp = snapshot.warp.clamp(1, propagation.max_warp());
+    snapshots.focus = snapshot.focus;
+    clock.0 = snapshot.clock;
+    jumps.send(ClockJumped);
+}
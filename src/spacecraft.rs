@@ -0,0 +1,263 @@
+use bevy::{math::DVec3, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::*,
+    forces::{NonGravitational, RadiationPressure},
+    label,
+    maneuver::ManeuverPlan,
+    BodyBundle, CircleSize, Coord, Kind, Mass, Primary, Radius, SimClock, Velocity,
+};
+
+/// Impulsive change of velocity at a given time.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Burn {
+    /// Simulated seconds since the start of the run
+    pub time: f64,
+    /// Direction in the local frame of the primary: prograde, normal and
+    /// radial (outward) components
+    pub direction: DVec3,
+    /// m/s
+    pub delta_v: f64,
+}
+
+impl Burn {
+    /// Direction of the burn for a spacecraft at the given position and
+    /// velocity relative to its primary.
+    pub fn heading(&self, position: DVec3, velocity: DVec3) -> DVec3 {
+        let (prograde, normal, radial) = local_frame(position, velocity, DVec3::ZERO, DVec3::ZERO);
+        (prograde * self.direction.x + normal * self.direction.y + radial * self.direction.z)
+            .normalize_or_zero()
+    }
+}
+
+#[derive(Component, Clone, Serialize, Deserialize)]
+pub struct Spacecraft {
+    pub dry_mass: f64,
+    pub propellant: f64,
+    /// Specific impulse, s
+    pub isp: f64,
+    /// Burns to perform, in any order; a burn fires on the step its time
+    /// falls in, so jumping the clock back repeats it
+    pub burns: Vec<Burn>,
+}
+
+impl Spacecraft {
+    /// Velocity change left with the remaining propellant.
+    pub fn delta_v_budget(&self) -> f64 {
+        self.isp * STANDARD_GRAVITY * ((self.dry_mass + self.propellant) / self.dry_mass).ln()
+    }
+
+    /// Performs a burn from the given state relative to the primary, spending
+    /// propellant by the rocket equation, and returns the change of velocity.
+    /// A burn the remaining propellant cannot pay for in full is cut short.
+    pub fn execute(&mut self, name: &str, burn: &Burn, position: DVec3, velocity: DVec3) -> DVec3 {
+        let delta_v = burn.delta_v.min(self.delta_v_budget());
+        let mass = self.dry_mass + self.propellant;
+        let final_mass = mass / (delta_v / (self.isp * STANDARD_GRAVITY)).exp();
+        self.propellant = (final_mass - self.dry_mass).max(0.0);
+
+        info!(
+            "{} burned {:.1} of {:.1} m/s, {:.1} kg of propellant left",
+            name, delta_v, burn.delta_v, self.propellant
+        );
+        burn.heading(position, velocity) * delta_v
+    }
+}
+
+pub struct CraftSpec {
+    pub name: &'static str,
+    pub primary: &'static str,
+    pub dry_mass: f64,
+    pub propellant: f64,
+    pub isp: f64,
+    /// Initial circular orbit radius around the primary, m
+    pub distance: f64,
+    pub velocity: f32,
+    pub inclination: f32,
+    pub burns: &'static [Burn],
+}
+
+pub const SPACECRAFT: &[CraftSpec] = &[CraftSpec {
+    name: "Lunar Probe",
+    primary: "Earth",
+    dry_mass: 1500.0,
+    propellant: 1000.0,
+    isp: 320.0,
+    distance: GEO_DISTANCE,
+    velocity: GEO_VEL,
+    inclination: 0.0,
+    // Hohmann transfer from geostationary orbit to the Moon's distance, timed
+    // so the Moon is there on arrival
+    burns: &[Burn {
+        time: 62_040.0,
+        direction: DVec3::X,
+        delta_v: 1053.0,
+    }],
+}];
+
+pub fn spawn_spacecraft(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    bodies: Query<(Entity, &Name, &Coord, &Velocity)>,
+) {
+    let plan = ManeuverPlan::load();
+    for spec in SPACECRAFT {
+        let Some((primary, _, coord, velocity)) = bodies
+            .iter()
+            .find(|(_, name, ..)| name.as_str() == spec.primary)
+        else {
+            continue;
+        };
+
+        spawn_craft(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &asset_server,
+            spec.name,
+            primary,
+            coord.0 + DVec3::new(spec.distance, 0.0, 0.0),
+            velocity.0
+                + (Quat::from_rotation_x(spec.inclination) * Vec3::new(0.0, spec.velocity, 0.0))
+                    .as_dvec3(),
+            Spacecraft {
+                dry_mass: spec.dry_mass,
+                propellant: spec.propellant,
+                isp: spec.isp,
+                burns: plan.burns(spec.name).unwrap_or_else(|| spec.burns.to_vec()),
+            },
+        );
+    }
+}
+
+/// Spawns a spacecraft at the given absolute state, orbiting `primary`.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_craft(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    asset_server: &AssetServer,
+    name: &str,
+    primary: Entity,
+    coord: DVec3,
+    velocity: DVec3,
+    spacecraft: Spacecraft,
+) -> Entity {
+    let entity = commands
+        .spawn((
+            BodyBundle {
+                pbr: PbrBundle {
+                    mesh: meshes.add(Mesh::from(shape::Cube {
+                        size: (CRAFT_RADIUS * SCALE) as f32,
+                    })),
+                    material: materials.add(StandardMaterial {
+                        base_color: Color::SILVER,
+                        ..default()
+                    }),
+                    transform: Transform::from_translation((coord * SCALE).as_vec3()),
+                    ..default()
+                },
+                mass: Mass(spacecraft.dry_mass + spacecraft.propellant),
+                radius: Radius(CRAFT_RADIUS),
+                velocity: Velocity(velocity),
+                coord: Coord(coord),
+                circle_size: CircleSize(0.004),
+                ..default()
+            },
+            Kind::Spacecraft,
+            Name::new(name.to_string()),
+            Primary(primary),
+            spacecraft,
+            NonGravitational::new(RadiationPressure::default()),
+        ))
+        .id();
+
+    label(
+        commands,
+        &TextStyle {
+            font: asset_server.load("PressStart2P-Regular.ttf"),
+            font_size: 12.0,
+            color: Color::ANTIQUE_WHITE,
+        },
+        entity,
+        &format!("{}: {}", Kind::Spacecraft.title(), name),
+        0.0,
+        (0.05 * AU * SCALE) as f32,
+    );
+    entity
+}
+
+/// Local frame of a body relative to its primary: prograde, orbit normal and
+/// radial outward directions.
+pub fn local_frame(
+    coord: DVec3,
+    velocity: DVec3,
+    primary_coord: DVec3,
+    primary_velocity: DVec3,
+) -> (DVec3, DVec3, DVec3) {
+    let position = coord - primary_coord;
+    let prograde = (velocity - primary_velocity).normalize();
+    let normal = position.cross(prograde).normalize();
+    (prograde, normal, prograde.cross(normal))
+}
+
+/// Fires the burns falling in the current step.
+#[allow(clippy::type_complexity)]
+pub fn fire_engines(
+    clock: Res<SimClock>,
+    mut crafts: Query<(
+        &Name,
+        &mut Spacecraft,
+        &mut Mass,
+        &mut Velocity,
+        &Coord,
+        &Primary,
+    )>,
+    primaries: Query<(&Coord, &Velocity), Without<Spacecraft>>,
+) {
+    let step = clock.0..clock.0 + DT as f64;
+    for (name, mut craft, mut mass, mut velocity, coord, primary) in &mut crafts {
+        let burns: Vec<_> = craft
+            .burns
+            .iter()
+            .filter(|burn| step.contains(&burn.time))
+            .copied()
+            .collect();
+        for burn in burns {
+            let Ok((primary_coord, primary_velocity)) = primaries.get(primary.0) else {
+                continue;
+            };
+            let delta_v = craft.execute(
+                name,
+                &burn,
+                coord.0 - primary_coord.0,
+                velocity.0 - primary_velocity.0,
+            );
+            velocity.0 += delta_v;
+            mass.0 = craft.dry_mass + craft.propellant;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{setup, tests::headless_app};
+
+    #[test]
+    fn startup_launches_the_lunar_probe() {
+        let mut app = headless_app();
+        app.add_systems(Startup, (setup, apply_deferred, spawn_spacecraft).chain());
+        app.update();
+        let world = &mut app.world;
+        let burns = world
+            .query::<(&Name, &Spacecraft)>()
+            .iter(world)
+            .find(|(name, _)| name.as_str() == "Lunar Probe")
+            .map(|(_, craft)| craft.burns.len());
+        assert_eq!(burns, Some(1));
+    }
+}
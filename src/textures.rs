@@ -0,0 +1,243 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::{
+    asset::io::file::FileAssetReader,
+    math::Vec3A,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+/// Look used for the generated fallback textures.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Surface {
+    Star,
+    Terrestrial,
+    Rock,
+    Cratered,
+    Ice,
+    GasGiant,
+}
+
+/// Texture maps of a body. Images named `textures/<body>_<map>.png` in the
+/// assets folder take precedence; albedo and normal maps are generated otherwise.
+pub struct BodyTextures {
+    pub albedo: Handle<Image>,
+    pub normal: Handle<Image>,
+    pub night: Option<Handle<Image>>,
+    pub clouds: Option<Handle<Image>>,
+}
+
+impl BodyTextures {
+    pub fn load(
+        name: &str,
+        surface: Surface,
+        color: Color,
+        asset_server: &AssetServer,
+        images: &mut Assets<Image>,
+    ) -> Self {
+        let load = |map: &str| {
+            let path = format!("textures/{}_{}.png", name.to_lowercase(), map);
+            FileAssetReader::get_base_path()
+                .join("assets")
+                .join(&path)
+                .exists()
+                .then(|| asset_server.load(path))
+        };
+
+        let (albedo, normal) = match (load("albedo"), load("normal")) {
+            (Some(albedo), Some(normal)) => (albedo, normal),
+            (albedo, normal) => {
+                let (generated_albedo, generated_normal) = generate(name, surface, color);
+                (
+                    albedo.unwrap_or_else(|| images.add(generated_albedo)),
+                    normal.unwrap_or_else(|| images.add(generated_normal)),
+                )
+            }
+        };
+
+        BodyTextures {
+            albedo,
+            normal,
+            night: load("night"),
+            clouds: load("clouds"),
+        }
+    }
+}
+
+/// Generates an equirectangular albedo map and a matching tangent-space normal map.
+fn generate(name: &str, surface: Surface, color: Color) -> (Image, Image) {
+    let (width, height) = match surface {
+        Surface::Star | Surface::Terrestrial | Surface::GasGiant => (512, 256),
+        _ => (256, 128),
+    };
+    let seed = name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    let craters = match surface {
+        Surface::Cratered => scatter_craters(seed, 60),
+        Surface::Ice => scatter_craters(seed, 8),
+        _ => Vec::new(),
+    };
+    let base = Vec3::new(color.r(), color.g(), color.b());
+
+    let mut albedo = Vec::with_capacity(width * height * 4);
+    let mut heights = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let latitude = PI / 2.0 - (y as f32 + 0.5) / height as f32 * PI;
+        for x in 0..width {
+            let longitude = (x as f32 + 0.5) / width as f32 * TAU;
+            // Sample noise on the sphere itself so the map has no seam or pinched poles
+            let point = Vec3A::new(
+                latitude.cos() * longitude.cos(),
+                latitude.cos() * longitude.sin(),
+                latitude.sin(),
+            );
+            let (rgb, height) = shade(surface, base, point, latitude, seed, &craters);
+            albedo.extend(rgb.to_array().map(|c| (c.clamp(0.0, 1.0) * 255.0) as u8));
+            albedo.push(255);
+            heights.push(height);
+        }
+    }
+
+    let mut normal = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let h = |dx: isize, dy: isize| {
+                let x = (x as isize + dx).rem_euclid(width as isize) as usize;
+                let y = (y as isize + dy).clamp(0, height as isize - 1) as usize;
+                heights[y * width + x]
+            };
+            let n = Vec3::new(h(-1, 0) - h(1, 0), h(0, 1) - h(0, -1), 0.5).normalize();
+            normal.extend(((n + 1.0) * 0.5).to_array().map(|c| (c * 255.0) as u8));
+            normal.push(255);
+        }
+    }
+
+    (
+        image(width, height, albedo, TextureFormat::Rgba8UnormSrgb),
+        image(width, height, normal, TextureFormat::Rgba8Unorm),
+    )
+}
+
+pub(crate) fn image(width: usize, height: usize, data: Vec<u8>, format: TextureFormat) -> Image {
+    Image::new(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+    )
+}
+
+/// Color and relative height of a surface point.
+fn shade(
+    surface: Surface,
+    base: Vec3,
+    point: Vec3A,
+    latitude: f32,
+    seed: u32,
+    craters: &[(Vec3A, f32)],
+) -> (Vec3, f32) {
+    let noise = fbm(point * 4.0, seed, 5);
+    match surface {
+        Surface::Star => {
+            let granules = fbm(point * 40.0, seed, 3);
+            (base * (0.8 + 0.4 * granules), 0.0)
+        }
+        Surface::Terrestrial => {
+            if noise < 0.52 {
+                (Vec3::new(0.05, 0.15, 0.45) * (0.8 + 0.4 * noise), 0.0)
+            } else if latitude.abs() > 1.2 + 0.2 * noise {
+                (Vec3::splat(0.95), noise)
+            } else {
+                (base * (0.6 + 0.6 * fbm(point * 16.0, seed, 3)), noise)
+            }
+        }
+        Surface::Rock => (base * (0.6 + 0.7 * noise), noise),
+        Surface::Cratered | Surface::Ice => {
+            let crater = craters
+                .iter()
+                .filter(|&&(center, radius)| point.dot(center) > (radius * 1.4).cos())
+                .map(|&(center, radius)| crater_profile(point.dot(center).acos() / radius))
+                .sum::<f32>();
+            let detail = if surface == Surface::Ice {
+                // Bright ice crossed by dark lineae
+                let lineae = (1.0 - (fbm(point * 8.0, seed ^ 0x5bd1e995, 3) - 0.5).abs() * 30.0)
+                    .clamp(0.0, 1.0);
+                0.95 + 0.15 * noise - 0.35 * lineae
+            } else {
+                0.7 + 0.5 * noise
+            };
+            (base * (detail + 0.4 * crater), noise * 0.3 + crater)
+        }
+        Surface::GasGiant => {
+            let turbulence = fbm(point * Vec3A::new(3.0, 3.0, 12.0), seed, 4);
+            let band = (latitude * 14.0 + turbulence * 3.0).sin();
+            (base * (0.85 + 0.2 * band + 0.1 * noise), 0.0)
+        }
+    }
+}
+
+/// Depression with a raised rim; `distance` is in crater radii.
+fn crater_profile(distance: f32) -> f32 {
+    if distance < 1.0 {
+        distance * distance - 0.7
+    } else if distance < 1.4 {
+        0.3 * (1.0 - (distance - 1.0) / 0.4)
+    } else {
+        0.0
+    }
+}
+
+/// Random crater centers and angular radii, many small and few large.
+fn scatter_craters(seed: u32, count: u32) -> Vec<(Vec3A, f32)> {
+    (0..count)
+        .map(|i| {
+            let z = hash(seed, i, 1, 0) * 2.0 - 1.0;
+            let phi = hash(seed, i, 2, 0) * TAU;
+            let r = (1.0 - z * z).sqrt();
+            let center = Vec3A::new(r * phi.cos(), r * phi.sin(), z);
+            (center, 0.02 + 0.2 * hash(seed, i, 3, 0).powi(4))
+        })
+        .collect()
+}
+
+/// Fractal value noise in `[0, 1]`.
+fn fbm(point: Vec3A, seed: u32, octaves: u32) -> f32 {
+    let (mut sum, mut amplitude, mut frequency, mut total) = (0.0, 0.5, 1.0, 0.0);
+    for octave in 0..octaves {
+        sum += amplitude * value_noise(point * frequency, seed.wrapping_add(octave));
+        total += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum / total
+}
+
+fn value_noise(point: Vec3A, seed: u32) -> f32 {
+    let cell = point.floor();
+    let t = point - cell;
+    let t = t * t * (3.0 - 2.0 * t);
+    let (x, y, z) = (cell.x as i32, cell.y as i32, cell.z as i32);
+    let corner = |dx: i32, dy: i32, dz: i32| hash(seed, x + dx, y + dy, z + dz);
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), t.x);
+    let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), t.x);
+    let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), t.x);
+    let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), t.x);
+    lerp(lerp(x00, x10, t.y), lerp(x01, x11, t.y), t.z)
+}
+
+/// Hashes lattice coordinates to `[0, 1)`.
+fn hash<T: Into<i64>>(seed: u32, x: T, y: T, z: T) -> f32 {
+    let mut h = seed as u64;
+    for v in [x.into(), y.into(), z.into()] {
+        h = (h ^ v as u64).wrapping_mul(0x9e3779b97f4a7c15);
+        h ^= h >> 29;
+    }
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
@@ -0,0 +1,335 @@
+use std::f64::consts::{PI, TAU};
+
+use bevy::{
+    math::{DQuat, DVec3},
+    prelude::*,
+};
+
+use crate::{
+    constants::*,
+    format_distance,
+    porkchop::{transfer_ends, Body, Porkchop},
+    spacecraft::{spawn_craft, Burn, Spacecraft},
+    unique_name, Kind, Mass, Ordinal, SimClock,
+};
+
+/// Spacecraft launched on a transfer carry enough propellant for it with a
+/// tenth to spare.
+const LAUNCH_DRY_MASS: f64 = 1000.0;
+const LAUNCH_ISP: f64 = 450.0;
+
+/// `K` shows transfers between circular orbits from the transfer origin to
+/// the focused body, `Y` and `U` launch a spacecraft on the Hohmann and
+/// bi-elliptic transfer, `-` and `=` lower and raise the bi-elliptic apoapsis.
+#[derive(Resource)]
+pub struct TransferCalculator {
+    pub visible: bool,
+    /// Bi-elliptic apoapsis in units of the larger orbit radius
+    pub apoapsis: f64,
+}
+
+impl Default for TransferCalculator {
+    fn default() -> Self {
+        TransferCalculator {
+            visible: false,
+            apoapsis: 2.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct TransferPanel;
+
+/// Impulsive transfer between coplanar circular orbits.
+pub struct Transfer {
+    /// Time after departure and prograde delta-v of each burn
+    pub burns: Vec<(f64, f64)>,
+    pub flight_time: f64,
+    /// Angle travelled around the central body
+    pub sweep: f64,
+}
+
+impl Transfer {
+    pub fn delta_v(&self) -> f64 {
+        self.burns.iter().map(|burn| burn.1.abs()).sum()
+    }
+}
+
+fn circular(mu: f64, r: f64) -> f64 {
+    (mu / r).sqrt()
+}
+
+/// Speed at distance `r` on an orbit of semi-major axis `a`.
+fn vis_viva(mu: f64, r: f64, a: f64) -> f64 {
+    (mu * (2.0 / r - 1.0 / a)).sqrt()
+}
+
+fn half_period(mu: f64, a: f64) -> f64 {
+    PI * (a.powi(3) / mu).sqrt()
+}
+
+pub fn hohmann(mu: f64, r1: f64, r2: f64) -> Transfer {
+    let a = (r1 + r2) / 2.0;
+    let flight_time = half_period(mu, a);
+    Transfer {
+        burns: vec![
+            (0.0, vis_viva(mu, r1, a) - circular(mu, r1)),
+            (flight_time, circular(mu, r2) - vis_viva(mu, r2, a)),
+        ],
+        flight_time,
+        sweep: PI,
+    }
+}
+
+/// Two half ellipses meeting at apoapsis `rb`, cheaper than a Hohmann
+/// transfer when the orbit radii differ by a factor of about 12 or more.
+pub fn bi_elliptic(mu: f64, r1: f64, r2: f64, rb: f64) -> Transfer {
+    let (a1, a2) = ((r1 + rb) / 2.0, (r2 + rb) / 2.0);
+    let (t1, t2) = (half_period(mu, a1), half_period(mu, a2));
+    Transfer {
+        burns: vec![
+            (0.0, vis_viva(mu, r1, a1) - circular(mu, r1)),
+            (t1, vis_viva(mu, rb, a2) - vis_viva(mu, rb, a1)),
+            (t1 + t2, circular(mu, r2) - vis_viva(mu, r2, a2)),
+        ],
+        flight_time: t1 + t2,
+        sweep: TAU,
+    }
+}
+
+/// Circular orbits of the two ends around the body they share.
+struct Geometry {
+    title: String,
+    central: Entity,
+    central_mass: f64,
+    mu: f64,
+    /// Position and velocity of the origin relative to the central body
+    origin: (DVec3, DVec3),
+    origin_mass: f64,
+    target: DVec3,
+    target_name: String,
+}
+
+impl Geometry {
+    fn new(bodies: &[Body], origin: Entity, target: Entity) -> Result<Self, String> {
+        let (title, central, origin, target) = transfer_ends(bodies, origin, target)?;
+        let body = |entity: Entity| bodies.iter().find(|body| body.0 == entity).unwrap();
+        let (c, o, t) = (body(central), body(origin), body(target));
+        Ok(Geometry {
+            title,
+            central,
+            central_mass: c.2 .0,
+            mu: G * c.2 .0,
+            origin: (o.3 .0 - c.3 .0, o.4 .0 - c.4 .0),
+            origin_mass: o.2 .0,
+            target: t.3 .0 - c.3 .0,
+            target_name: t.1.to_string(),
+        })
+    }
+
+    fn normal(&self) -> DVec3 {
+        self.origin.0.cross(self.origin.1).normalize()
+    }
+
+    fn radii(&self) -> (f64, f64) {
+        (self.origin.0.length(), self.target.length())
+    }
+
+    /// Mean motions of circular orbits at the two radii.
+    fn motions(&self) -> (f64, f64) {
+        let (r1, r2) = self.radii();
+        ((self.mu / r1.powi(3)).sqrt(), (self.mu / r2.powi(3)).sqrt())
+    }
+
+    fn synodic_period(&self) -> f64 {
+        let (n1, n2) = self.motions();
+        TAU / (n1 - n2).abs()
+    }
+
+    /// Time until a departure from `from` makes the target arrive with the
+    /// spacecraft.
+    fn wait(&self, from: DVec3, transfer: &Transfer) -> f64 {
+        let (n1, n2) = self.motions();
+        let phase = self
+            .normal()
+            .dot(from.cross(self.target))
+            .atan2(from.dot(self.target));
+        let required = transfer.sweep - n2 * transfer.flight_time;
+        ((required - phase) / (n2 - n1)).rem_euclid(self.synodic_period())
+    }
+}
+
+pub fn spawn_transfer_panel(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("PressStart2P-Regular.ttf"),
+                font_size: 10.0,
+                color: Color::ANTIQUE_WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(40.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        TransferPanel,
+    ));
+}
+
+fn days(seconds: f64) -> f64 {
+    seconds / 86400.0
+}
+
+fn describe(transfer: &Transfer) -> String {
+    let burns: Vec<_> = transfer
+        .burns
+        .iter()
+        .map(|burn| format!("{:.2}", burn.1 / 1e3))
+        .collect();
+    format!(
+        "{} = {:.2} km/s, {:.1} d",
+        burns.join(" + "),
+        transfer.delta_v() / 1e3,
+        days(transfer.flight_time)
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn calculate_transfers(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    clock: Res<SimClock>,
+    porkchop: Res<Porkchop>,
+    mut calculator: ResMut<TransferCalculator>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    bodies: Query<Body>,
+    names: Query<&Name, With<Mass>>,
+    focusable: Query<Entity, With<Mass>>,
+    camera: Query<&Ordinal>,
+    mut panel: Query<&mut Text, With<TransferPanel>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::K) {
+        calculator.visible = !calculator.visible;
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        calculator.apoapsis = (calculator.apoapsis / 1.25).max(1.0);
+    }
+    if keyboard_input.just_pressed(KeyCode::Equals) {
+        calculator.apoapsis *= 1.25;
+    }
+    let text = &mut panel.single_mut().sections[0].value;
+    if !calculator.visible {
+        text.clear();
+        return;
+    }
+
+    let bodies: Vec<_> = bodies
+        .iter()
+        .filter(|body| !matches!(body.5, Kind::Spacecraft | Kind::Particle))
+        .collect();
+    let origin = porkchop.origin(&bodies);
+    let target = focusable.iter().nth(camera.single().0);
+    let geometry = match (origin, target) {
+        (Some(origin), Some(target)) => Geometry::new(&bodies, origin, target),
+        _ => Err("Focus a body to transfer to".to_string()),
+    };
+    let geometry = match geometry {
+        Ok(geometry) => geometry,
+        Err(message) => {
+            *text = message;
+            return;
+        }
+    };
+
+    let (r1, r2) = geometry.radii();
+    let rb = r1.max(r2) * calculator.apoapsis;
+    let transfers = [
+        ("Hohmann", hohmann(geometry.mu, r1, r2)),
+        ("Bi-elliptic", bi_elliptic(geometry.mu, r1, r2, rb)),
+    ];
+    let mut lines = vec![
+        geometry.title.clone(),
+        format!("Synodic period {:.1} d", days(geometry.synodic_period())),
+    ];
+    for (name, transfer) in &transfers {
+        lines.push(format!("{}: {}", name, describe(transfer)));
+        lines.push(format!(
+            "  window in {:.1} d",
+            days(geometry.wait(geometry.origin.0, transfer))
+        ));
+    }
+    lines.push(format!("Bi-elliptic apoapsis {}", format_distance(rb)));
+    *text = lines.join("\n");
+
+    let launch = match (
+        keyboard_input.just_pressed(KeyCode::Y),
+        keyboard_input.just_pressed(KeyCode::U),
+    ) {
+        (true, _) => &transfers[0],
+        (_, true) => &transfers[1],
+        _ => return,
+    };
+
+    // The spacecraft starts on the origin's orbit but far enough behind it
+    // that its pull does not spoil the transfer
+    let hill = r1 * (geometry.origin_mass / (3.0 * geometry.central_mass)).cbrt();
+    let lag = DQuat::from_axis_angle(geometry.normal(), -TRANSFER_CLEARANCE * hill / r1);
+    let start = lag * geometry.origin.0;
+    let speed = circular(geometry.mu, r1);
+    let velocity = geometry.normal().cross(start).normalize() * speed;
+    let departure = clock.0 + geometry.wait(start, &launch.1);
+
+    let propellant = LAUNCH_DRY_MASS
+        * ((launch.1.delta_v() * 1.1 / (LAUNCH_ISP * STANDARD_GRAVITY)).exp() - 1.0);
+    let Some(central) = bodies.iter().find(|body| body.0 == geometry.central) else {
+        return;
+    };
+    spawn_craft(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &asset_server,
+        &unique_name(
+            format!("{} to {}", launch.0, geometry.target_name),
+            names.iter().map(|name| name.as_str()),
+        ),
+        geometry.central,
+        central.3 .0 + start,
+        central.4 .0 + velocity,
+        Spacecraft {
+            dry_mass: LAUNCH_DRY_MASS,
+            propellant,
+            isp: LAUNCH_ISP,
+            burns: launch
+                .1
+                .burns
+                .iter()
+                .map(|&(offset, delta_v)| Burn {
+                    time: departure + offset,
+                    direction: DVec3::X * delta_v.signum(),
+                    delta_v: delta_v.abs(),
+                })
+                .collect(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn earth_to_mars_hohmann() {
+        let transfer = hohmann(G * SUN_MASS, AU, 1.524 * AU);
+        let (departure, arrival) = (transfer.burns[0].1, transfer.burns[1].1);
+        assert!((departure - 2945.0).abs() < 20.0, "{} m/s", departure);
+        assert!((arrival - 2649.0).abs() < 20.0, "{} m/s", arrival);
+        let days = transfer.flight_time / 86400.0;
+        assert!((days - 259.0).abs() < 2.0, "{} days", days);
+    }
+}